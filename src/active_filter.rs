@@ -0,0 +1,98 @@
+//! Contains the logic to save the active filter or study session when the CLI exits, so that it can
+//! be restored the next time the library is opened.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::{fs::remove_file, path::Path};
+use trane::data::filter::{SessionPart, StudySessionData, UnitFilter};
+use ustr::Ustr;
+
+use crate::json_file::{read_json, write_json};
+
+/// The name of the file inside the `.trane` directory where the active filter is stored.
+pub const ACTIVE_FILTER_FILE: &str = "active_filter.json";
+
+/// The filter or study session used to select the exercises shown to the user.
+pub(crate) enum ActiveFilter {
+    /// A unit filter, set directly or from a saved filter.
+    Filter(UnitFilter),
+
+    /// A filter on individual exercises.
+    Exercises(Vec<Ustr>),
+
+    /// A study session.
+    StudySession(StudySessionData),
+}
+
+impl ActiveFilter {
+    /// Reads the active filter stored in the given config directory, if any.
+    pub fn load(config_dir: &Path) -> Result<Option<Self>> {
+        let path = config_dir.join(ACTIVE_FILTER_FILE);
+        let Some(mut value) = read_json::<Value>(&path, "active filter")? else {
+            return Ok(None);
+        };
+        let active_filter = if let Some(filter) = value.get_mut("filter") {
+            Some(Self::Filter(serde_json::from_value(filter.take())?))
+        } else if let Some(exercise_ids) = value.get_mut("exercises") {
+            Some(Self::Exercises(serde_json::from_value(
+                exercise_ids.take(),
+            )?))
+        } else if let Some(study_session) = value.get_mut("study_session") {
+            Some(Self::StudySession(serde_json::from_value(
+                study_session.take(),
+            )?))
+        } else {
+            None
+        };
+        Ok(active_filter)
+    }
+
+    /// Writes the given active filter to the given config directory. If there's no active filter,
+    /// the file is removed instead.
+    pub fn save(active_filter: Option<&Self>, config_dir: &Path) -> Result<()> {
+        let path = config_dir.join(ACTIVE_FILTER_FILE);
+        let value = match active_filter {
+            None => {
+                if path.exists() {
+                    remove_file(&path).with_context(|| {
+                        format!("failed to remove active filter file at {}", path.display())
+                    })?;
+                }
+                return Ok(());
+            }
+            Some(Self::Filter(filter)) => json!({ "filter": filter }),
+            Some(Self::Exercises(exercise_ids)) => json!({ "exercises": exercise_ids }),
+            Some(Self::StudySession(study_session)) => json!({ "study_session": study_session }),
+        };
+        write_json(&path, &value, "active filter")
+    }
+
+    /// Returns the IDs of the units referenced by the given unit filter.
+    fn filter_unit_ids(filter: &UnitFilter) -> Vec<Ustr> {
+        match filter {
+            UnitFilter::CourseFilter { course_ids } => course_ids.clone(),
+            UnitFilter::LessonFilter { lesson_ids } => lesson_ids.clone(),
+            UnitFilter::Dependents { unit_ids } | UnitFilter::Dependencies { unit_ids, .. } => {
+                unit_ids.clone()
+            }
+            UnitFilter::MetadataFilter { .. } | UnitFilter::ReviewListFilter => vec![],
+        }
+    }
+
+    /// Returns the IDs of all the units referenced by the active filter.
+    pub fn unit_ids(&self) -> Vec<Ustr> {
+        match self {
+            Self::Filter(filter) => Self::filter_unit_ids(filter),
+            Self::Exercises(exercise_ids) => exercise_ids.clone(),
+            Self::StudySession(study_session) => study_session
+                .definition
+                .parts
+                .iter()
+                .flat_map(|part| match part {
+                    SessionPart::UnitFilter { filter, .. } => Self::filter_unit_ids(filter),
+                    SessionPart::SavedFilter { .. } | SessionPart::NoFilter { .. } => vec![],
+                })
+                .collect(),
+        }
+    }
+}
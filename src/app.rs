@@ -1,36 +1,130 @@
 //! Contains the state of the application and the logic to interact with Trane.
 
-use anyhow::{anyhow, bail, ensure, Result};
-use chrono::{Datelike, Local, TimeZone, Utc};
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use chrono::{Datelike, Duration, Local, SecondsFormat, TimeZone, Utc};
 use indoc::formatdoc;
-use std::{fs::File, io::Write, path::Path};
+use rusqlite::{Connection, OpenFlags};
+use serde_json::json;
+use std::{
+    collections::BTreeSet,
+    fs::{read_dir, read_to_string, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    rc::Rc,
+    thread::sleep,
+    time::Duration as StdDuration,
+};
 use trane::{
     blacklist::Blacklist,
-    course_library::CourseLibrary,
+    course_library::{CourseLibrary, COURSE_MANIFEST_FILENAME},
     data::{
+        course_generator::Instrument,
         filter::{
-            ExerciseFilter, FilterOp, FilterType, KeyValueFilter, StudySessionData, UnitFilter,
+            ExerciseFilter, FilterOp, FilterType, KeyValueFilter, SavedFilter, StudySessionData,
+            UnitFilter,
         },
-        ExerciseManifest, MasteryScore, SchedulerOptions, UnitType,
+        BasicAsset, ExerciseAsset, ExerciseManifest, ExerciseTrial, ExerciseType, MasteryScore,
+        MasteryWindow, PassingScoreOptions, SchedulerOptions, UnitType,
     },
     filter_manager::FilterManager,
     graph::UnitGraph,
     practice_stats::PracticeStats,
+    preferences_manager::PreferencesManager,
     repository_manager::RepositoryManager,
     review_list::ReviewList,
     scheduler::ExerciseScheduler,
     scorer::{ExerciseScorer, SimpleScorer},
     study_session_manager::StudySessionManager,
     transcription_downloader::TranscriptionDownloader,
-    Trane,
+    Trane, FILTERS_DIR, PRACTICE_STATS_PATH, TRANE_CONFIG_DIR_PATH,
 };
 use ustr::Ustr;
 
-use crate::display::{DisplayAnswer, DisplayAsset, DisplayExercise};
-use crate::{built_info, cli::KeyValue};
+use crate::display::{
+    ascii_bar, color_by_score, column_width, display_exercise_content, dump_exercise,
+    flip_flashcard, mastery_badge, print_json, print_notes, print_warning, relative_time,
+    remaining_time, render_exercise, sparkline, terminal_width, DisplayAnswer, DisplayAsset,
+    DisplayExercise,
+};
+use crate::{
+    active_filter::ActiveFilter,
+    bookmarks::Bookmarks,
+    built_info,
+    cli::{ExportFormat, InterruptBehavior, KeyValue, SearchField, SearchUnitType},
+    clipboard::copy_to_clipboard,
+    config::config_path,
+    helper::{SharedUnitIds, UnitIds},
+    json_file::write_json,
+    keys::{countdown, read_grade_key, GradeKey},
+    mantras::{add_to_lifetime_count, load_lifetime_count},
+    notes::{ExerciseNotes, TrialNotes},
+    pager::Pager,
+    picker::pick_unit,
+    recent::RecentExercises,
+    repositories::{
+        checkout_ref, commits_behind, fetch, is_network_error, sync_status, RepositoryRefs,
+        RepositoryUpdates, MANAGED_COURSES_DIR,
+    },
+    session::SessionStats,
+    suspensions::Suspensions,
+};
+
+/// The number of seconds in a day.
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// The maximum width of the bars in the charts printed to the terminal.
+const MAX_BAR_WIDTH: usize = 50;
+
+/// The maximum number of reviews simulated for each exercise when estimating the remaining
+/// reviews.
+const MAX_SIMULATED_REVIEWS: usize = 20;
+
+/// The maximum number of exercises skipped in a row because they were shown recently. Once reached,
+/// the next exercise is shown even if it is a repeat.
+const MAX_SKIPPED_REPEATS: usize = 10;
+
+/// The maximum number of exercises skipped in a row because they could not be displayed, for
+/// example because one of their files is missing. Once reached, the `next` command fails instead.
+const MAX_SKIPPED_BROKEN: usize = 10;
+
+/// The maximum number of batches requested in a row when all of their exercises belong to
+/// suspended units. Once reached, the `next` command fails instead.
+const MAX_SUSPENDED_BATCH_ATTEMPTS: usize = 5;
+
+/// The maximum number of characters of the matched text shown for each search result.
+const MAX_SNIPPET_LENGTH: usize = 40;
+
+/// The prompt shown when no course ID is included in it.
+const DEFAULT_PROMPT: &str = "trane >> ";
+
+/// The maximum number of characters of the course ID shown in the prompt.
+const MAX_PROMPT_ID_LENGTH: usize = 24;
+
+/// The value given in place of a unit ID to pick the unit interactively.
+const PICK_UNIT_ID: &str = "?";
+
+/// The number of times the update of a repository is attempted before giving up. Only updates that
+/// fail because of the network are retried.
+const MAX_UPDATE_ATTEMPTS: u32 = 3;
+
+/// The time to wait before retrying a failed update. It doubles after every failed attempt.
+const UPDATE_RETRY_DELAY: StdDuration = StdDuration::from_secs(2);
+
+/// The format in which commands print their results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    /// Human-readable text, usually aligned in columns.
+    #[default]
+    Text,
+
+    /// Machine-readable JSON.
+    Json,
+}
 
 /// Stores the app and its configuration.
 #[derive(Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub(crate) struct TraneApp {
     /// The instance of the Trane library.
     trane: Option<Trane>,
@@ -41,6 +135,19 @@ pub(crate) struct TraneApp {
     /// The study session used to select exercises.
     study_session: Option<StudySessionData>,
 
+    /// The exercises to which the filter is restricted. Trane's unit filters cannot select
+    /// individual exercises, so these are combined with a filter on their lessons and the batches
+    /// are narrowed down to them.
+    filter_exercise_ids: Option<Vec<Ustr>>,
+
+    /// The exercises shown in order when the filter is set to go through courses linearly. When
+    /// set, batches are built from these exercises instead of being requested from the scheduler.
+    linear_exercise_ids: Option<Vec<Ustr>>,
+
+    /// The position in the linear order from which the next batch starts, so that resetting the
+    /// batch resumes from the exercise the user was on instead of starting over.
+    linear_position: usize,
+
     /// The current batch of exercises.
     batch: Vec<ExerciseManifest>,
 
@@ -50,6 +157,88 @@ pub(crate) struct TraneApp {
     /// The score given to the current exercise. The score can be changed anytime before the next
     /// exercise is requested.
     current_score: Option<MasteryScore>,
+
+    /// The note attached to the score given to the current exercise, if any. It's saved along with
+    /// the trial when the score is submitted.
+    current_score_note: Option<String>,
+
+    /// The ID of the exercise, the timestamp, and the score submitted most recently in this
+    /// session, used to undo it.
+    last_submitted_score: Option<(Ustr, i64, MasteryScore)>,
+
+    /// The number of mantras recited by the open Trane instance that were already added to the
+    /// lifetime count, so that saving the count more than once does not add them twice.
+    saved_mantra_count: usize,
+
+    /// The number of exercises scored during this session.
+    session_stats: SessionStats,
+
+    /// The notes the user has written about individual exercises.
+    notes: Option<ExerciseNotes>,
+
+    /// The notes attached to individual trials.
+    trial_notes: Option<TrialNotes>,
+
+    /// The exercises the user has bookmarked.
+    bookmarks: Option<Bookmarks>,
+
+    /// The last time each managed repository was updated.
+    repository_updates: Option<RepositoryUpdates>,
+
+    /// The refs to which the managed repositories are pinned.
+    repository_refs: Option<RepositoryRefs>,
+
+    /// The units that are temporarily suspended.
+    suspensions: Option<Suspensions>,
+
+    /// Whether focus mode is enabled. In focus mode, exercises and answers are shown without the
+    /// course, lesson, and exercise IDs.
+    focus_mode: bool,
+
+    /// Whether the course ID of the current exercise is shown in the prompt.
+    course_in_prompt: bool,
+
+    /// Whether flashcards are shown back to front, with the back as the prompt and the front as
+    /// the answer.
+    flip: bool,
+
+    /// Whether the answer to the current exercise is shown right after it's scored.
+    auto_answer: bool,
+
+    /// The number of seconds the `answer` command waits before showing the answer, to encourage
+    /// attempting the exercise first.
+    reveal_delay: u64,
+
+    /// Whether a new batch is fetched and its first exercise shown as soon as a command resets
+    /// the batch, so that there's always a current exercise.
+    auto_next: bool,
+
+    /// Whether the batch was reset by the command being executed. It's cleared before each command
+    /// so that a reset does not carry over to later commands.
+    batch_reset: bool,
+
+    /// The exercises shown most recently. The `next` command skips exercises in this buffer to
+    /// avoid immediate repeats.
+    recent_exercises: RecentExercises,
+
+    /// The action taken when Ctrl-C is pressed at the prompt.
+    interrupt_behavior: InterruptBehavior,
+
+    /// The batch size set in the config file, used for libraries that do not set their own.
+    default_batch_size: Option<usize>,
+
+    /// The path to the history file, if it was changed in the config file.
+    history_path: Option<PathBuf>,
+
+    /// Whether the app is in read-only mode. In this mode, scores are not submitted and all the
+    /// commands that modify the library or its data are rejected.
+    read_only: bool,
+
+    /// The format used by the commands that support machine-readable output.
+    output_format: OutputFormat,
+
+    /// The IDs of the units in the open library, shared with the prompt helper to complete them.
+    unit_ids: SharedUnitIds,
 }
 
 impl TraneApp {
@@ -63,6 +252,32 @@ impl TraneApp {
         None
     }
 
+    /// Returns the version of the installed yt-dlp binary, if any.
+    fn yt_dlp_version() -> Option<String> {
+        let output = Command::new("yt-dlp").arg("--version").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Prints the versions of Trane, the CLI, and yt-dlp, along with the commit hash.
+    pub fn show_version() {
+        println!(
+            "Trane Version: {}",
+            Self::trane_version().unwrap_or_else(|| "UNKNOWN".to_string())
+        );
+        println!("CLI Version: {}", built_info::PKG_VERSION);
+        println!(
+            "Commit Hash: {}",
+            built_info::GIT_COMMIT_HASH.unwrap_or("UNKNOWN")
+        );
+        println!(
+            "yt-dlp Version: {}",
+            Self::yt_dlp_version().unwrap_or_else(|| "NOT INSTALLED".to_string())
+        );
+    }
+
     /// Returns the message shown every time Trane starts up.
     pub fn startup_message() -> String {
         formatdoc! {r#"
@@ -127,11 +342,28 @@ impl TraneApp {
         Ok(manifest.lesson_id)
     }
 
+    /// Returns an error if the app is in read-only mode. Called at the start of every operation
+    /// that modifies the library or its data.
+    fn ensure_writable(&self) -> Result<()> {
+        ensure!(
+            !self.read_only,
+            "cannot modify the library or its data in read-only mode"
+        );
+        Ok(())
+    }
+
+    /// Enables read-only mode, in which all the operations that modify the library or its data are
+    /// rejected.
+    pub fn set_read_only(&mut self) {
+        self.read_only = true;
+    }
+
     /// Submits the score for the current exercise.
     pub fn submit_current_score(&mut self) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
         if let Some(mastery_score) = &self.current_score {
+            self.ensure_writable()?;
             let curr_exercise = self.current_exercise()?;
             let timestamp = Utc::now().timestamp();
             self.trane.as_ref().unwrap().score_exercise(
@@ -139,7 +371,77 @@ impl TraneApp {
                 mastery_score.clone(),
                 timestamp,
             )?;
+            self.session_stats.record(mastery_score);
+            self.last_submitted_score = Some((curr_exercise.id, timestamp, mastery_score.clone()));
+            if let Some(note) = self.current_score_note.take() {
+                self.trial_notes
+                    .as_mut()
+                    .unwrap()
+                    .add_note(curr_exercise.id, timestamp, &note)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Submits the pending score for the current exercise before quitting, or drops it if
+    /// `discard` is true. Prints what happened to the score, if there was one.
+    pub fn settle_pending_score(&mut self, discard: bool) -> Result<()> {
+        if self.trane.is_none() || self.current_score.is_none() {
+            return Ok(());
+        }
+
+        if discard {
+            println!("Discarded the pending score for the current exercise");
+        } else {
+            self.submit_current_score()
+                .context("failed to submit the pending score, use quit --discard to drop it")?;
+            println!("Submitted the pending score for the current exercise");
         }
+        self.current_score = None;
+        Ok(())
+    }
+
+    /// Prints a summary of the exercises scored during this session.
+    pub fn show_session_stats(&self) {
+        self.session_stats.print();
+    }
+
+    /// Removes the score submitted most recently in this session from the practice stats. Only
+    /// the last score can be undone.
+    pub fn undo(&mut self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        let Some((exercise_id, timestamp, score)) = self.last_submitted_score.clone() else {
+            println!("Nothing to undo");
+            return Ok(());
+        };
+
+        // Trane does not expose a way to remove a single trial, so delete it from the database
+        // directly and invalidate the cached score of the exercise.
+        let connection = Connection::open(self.stats_db_path()?)?;
+        let num_deleted = connection.execute(
+            "DELETE FROM practice_stats WHERE id = (
+                SELECT id FROM practice_stats WHERE timestamp = ?2 AND unit_uid = (
+                    SELECT unit_uid FROM uids WHERE unit_id = ?1)
+                ORDER BY id DESC LIMIT 1);",
+            (exercise_id.as_str(), timestamp),
+        )?;
+        ensure!(
+            num_deleted == 1,
+            "the last score for exercise {exercise_id} was not found in the practice stats"
+        );
+        self.trane
+            .as_ref()
+            .unwrap()
+            .invalidate_cached_score(exercise_id);
+        self.session_stats.remove(&score);
+        self.trial_notes
+            .as_mut()
+            .unwrap()
+            .remove_note(exercise_id, timestamp)?;
+        self.last_submitted_score = None;
+        println!("Removed the last score submitted for exercise {exercise_id}");
         Ok(())
     }
 
@@ -149,9 +451,74 @@ impl TraneApp {
         // might be called before an instance of Trane is open.
         let _ = self.submit_current_score();
 
+        // Resume the linear order from the current exercise.
+        if let (Some(exercise_ids), Some(manifest)) =
+            (&self.linear_exercise_ids, self.batch.get(self.batch_index))
+        {
+            if let Some(position) = exercise_ids.iter().position(|id| *id == manifest.id) {
+                self.linear_position = position;
+            }
+        }
+
         self.batch.clear();
         self.batch_index = 0;
         self.current_score = None;
+        self.batch_reset = true;
+    }
+
+    /// Replaces the unit filter, dropping the exercises and the linear order that belonged to the
+    /// previous one. Every change to the filter goes through here.
+    fn replace_filter(&mut self, filter: Option<UnitFilter>) {
+        self.filter = filter;
+        self.filter_exercise_ids = None;
+        self.linear_exercise_ids = None;
+        self.linear_position = 0;
+    }
+
+    /// Enables or disables showing the answer right after scoring an exercise.
+    pub fn set_auto_answer(&mut self, enabled: bool) {
+        self.auto_answer = enabled;
+    }
+
+    /// Shows the answer to the current exercise if auto-answer is on and the exercise is a
+    /// flashcard with an answer. Otherwise, does nothing.
+    pub fn show_answer_after_score(&mut self) -> Result<()> {
+        if !self.auto_answer || self.trane.is_none() {
+            return Ok(());
+        }
+        let has_answer = matches!(
+            self.current_exercise()?.exercise_asset,
+            ExerciseAsset::FlashcardAsset {
+                back_path: Some(_),
+                ..
+            }
+        );
+        if has_answer {
+            println!();
+            self.show_answer()?;
+        }
+        Ok(())
+    }
+
+    /// Enables or disables showing a new exercise right after a command resets the batch.
+    pub fn set_auto_next(&mut self, enabled: bool) {
+        self.auto_next = enabled;
+    }
+
+    /// Forgets any batch reset made by a previous command. Called before executing each command.
+    pub fn clear_batch_reset(&mut self) {
+        self.batch_reset = false;
+    }
+
+    /// Shows the first exercise of a new batch if auto-next is on and the batch was reset by the
+    /// last command. Otherwise, does nothing. The reset is consumed either way.
+    pub fn show_next_after_reset(&mut self) -> Result<()> {
+        let batch_reset = std::mem::take(&mut self.batch_reset);
+        if !self.auto_next || !batch_reset || self.trane.is_none() {
+            return Ok(());
+        }
+        println!();
+        self.next()
     }
 
     /// Returns whether the unit with the given ID exists in the currently opened Trane library.
@@ -168,6 +535,7 @@ impl TraneApp {
     /// Adds the current exercise's course to the blacklist.
     pub fn blacklist_course(&mut self) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
 
         let course_id = self.current_exercise_course()?;
         self.trane.as_mut().unwrap().add_to_blacklist(course_id)?;
@@ -178,6 +546,7 @@ impl TraneApp {
     /// Adds the current exercise's lesson to the blacklist.
     pub fn blacklist_lesson(&mut self) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
 
         let lesson_id = self.current_exercise_lesson()?;
         self.trane.as_mut().unwrap().add_to_blacklist(lesson_id)?;
@@ -188,6 +557,7 @@ impl TraneApp {
     /// Adds the current exercise to the blacklist.
     pub fn blacklist_exercise(&mut self) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
 
         let manifest = self.current_exercise()?;
         self.trane.as_mut().unwrap().add_to_blacklist(manifest.id)?;
@@ -195,18 +565,25 @@ impl TraneApp {
         Ok(())
     }
 
-    /// Adds the unit with the given ID to the blacklist.
-    pub fn blacklist_unit(&mut self, unit_id: Ustr) -> Result<()> {
+    /// Adds the units with the given IDs to the blacklist. The batch is reset only once, after
+    /// all the units have been added.
+    pub fn blacklist_units(&mut self, unit_ids: &[Ustr]) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
-        ensure!(
-            self.unit_exists(unit_id)?,
-            "unit {} does not exist",
-            unit_id
-        );
+        self.ensure_writable()?;
+        for unit_id in unit_ids {
+            ensure!(
+                self.unit_exists(*unit_id)?,
+                "unit {} does not exist",
+                unit_id
+            );
+        }
 
-        self.trane.as_mut().unwrap().add_to_blacklist(unit_id)?;
+        let trane = self.trane.as_mut().unwrap();
+        let result = unit_ids
+            .iter()
+            .try_for_each(|unit_id| trane.add_to_blacklist(*unit_id));
         self.reset_batch();
-        Ok(())
+        Ok(result?)
     }
 
     /// Clears the unit filter if it's set.
@@ -214,17 +591,76 @@ impl TraneApp {
         if self.filter.is_none() {
             return;
         }
-        self.filter = None;
+        self.replace_filter(None);
         self.study_session = None;
         self.reset_batch();
     }
 
+    /// Prints the position of the current exercise in the batch, noting when the next exercise
+    /// will come from a new batch.
+    fn print_batch_position(&self) {
+        let position = self.batch_index + 1;
+        let batch_size = self.batch.len();
+        if position == batch_size {
+            println!(
+                "Exercise {position} of {batch_size} in the current batch \
+                (the next exercise starts a new batch)"
+            );
+        } else {
+            println!("Exercise {position} of {batch_size} in the current batch");
+        }
+        println!();
+    }
+
+    /// Displays the given exercise, which must be the current exercise, along with its position in
+    /// the batch and any notes stored for it.
+    fn display_exercise(&self, manifest: &ExerciseManifest) -> Result<()> {
+        self.print_batch_position();
+        if self.flip
+            && matches!(
+                manifest.exercise_asset,
+                ExerciseAsset::FlashcardAsset {
+                    back_path: None,
+                    ..
+                }
+            )
+        {
+            println!("This flashcard has no back, so it is shown front to back");
+            println!();
+        }
+        let manifest = &self.oriented_exercise(manifest);
+        if self.focus_mode {
+            display_exercise_content(manifest)?;
+        } else {
+            manifest.display_exercise()?;
+        }
+        if let Some(notes) = &self.notes {
+            print_notes(notes.get_notes(manifest.id));
+        }
+        Ok(())
+    }
+
     /// Displays the current exercise.
     pub fn current(&self) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
         let manifest = self.current_exercise()?;
-        manifest.display_exercise()
+        self.display_exercise(&manifest)
+    }
+
+    /// Prints only the ID of the current exercise so that it can be passed to other tools.
+    pub fn current_id(&self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        println!("{}", self.current_exercise()?.id);
+        Ok(())
+    }
+
+    /// Copies the ID of the current exercise to the clipboard.
+    pub fn copy_current_id(&self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        copy_to_clipboard(&self.current_exercise()?.id)
     }
 
     /// Returns the given course ID or the current exercise's course ID if the given ID is empty.
@@ -264,26 +700,376 @@ impl TraneApp {
         }
     }
 
-    /// Exports the dependent graph as a DOT file to the given path.
-    pub fn export_graph(&self, path: &Path) -> Result<()> {
+    /// Exports the dependent graph as a DOT file to the given path. If a unit is given, only the
+    /// units within `depth` dependency edges of it are exported.
+    pub fn export_graph(&self, path: &Path, around: Option<Ustr>, depth: usize) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
-        let dot_graph = self.trane.as_ref().unwrap().generate_dot_graph();
+        let dot_graph = match around {
+            Some(unit_id) => self.neighborhood_dot_graph(unit_id, depth)?,
+            None => self.trane.as_ref().unwrap().generate_dot_graph(),
+        };
         let mut file = File::create(path)?;
         file.write_all(dot_graph.as_bytes())?;
         Ok(())
     }
 
+    /// Returns the units reachable from the given units by following up to `depth` edges, using
+    /// the given function to find the neighbors of each unit. The given units are included.
+    fn reachable_units<I: IntoIterator<Item = Ustr>>(
+        unit_ids: &[Ustr],
+        depth: usize,
+        neighbors: impl Fn(Ustr) -> I,
+    ) -> BTreeSet<Ustr> {
+        let mut visited = BTreeSet::new();
+        let mut frontier = unit_ids.to_vec();
+        for level in 0..=depth {
+            let mut next_frontier = Vec::new();
+            for unit_id in frontier {
+                if !visited.insert(unit_id) || level == depth {
+                    continue;
+                }
+                next_frontier.extend(neighbors(unit_id));
+            }
+            frontier = next_frontier;
+        }
+        visited
+    }
+
+    /// Generates a DOT graph with the given course or lesson and the dependencies and dependents
+    /// within `depth` edges of it, in the same format used to export the full graph. The given unit
+    /// is drawn with a thicker outline.
+    fn neighborhood_dot_graph(&self, unit_id: Ustr, depth: usize) -> Result<String> {
+        let unit_type = self.get_unit_type(unit_id)?;
+        ensure!(
+            unit_type != UnitType::Exercise,
+            "Unit with ID {unit_id} is not a course or lesson"
+        );
+
+        let trane = self.trane.as_ref().unwrap();
+        let mut units = Self::reachable_units(&[unit_id], depth, |unit_id| {
+            trane.get_dependencies(unit_id).unwrap_or_default()
+        });
+        units.extend(Self::reachable_units(&[unit_id], depth, |unit_id| {
+            trane.get_dependents(unit_id).unwrap_or_default()
+        }));
+
+        let mut lines = vec!["digraph dependent_graph {".to_string()];
+        for node_id in &units {
+            let color = match trane.get_unit_type(*node_id) {
+                Some(UnitType::Course) => "red",
+                Some(UnitType::Lesson) => "blue",
+                _ => "gray",
+            };
+            let outline = if *node_id == unit_id {
+                ", penwidth=3"
+            } else {
+                ""
+            };
+            lines.push(format!(
+                "    \"{node_id}\" [color={color}, style=filled{outline}]"
+            ));
+
+            // Courses are connected to their starting lessons, as in the full graph.
+            let mut dependents: Vec<Ustr> = trane
+                .get_dependents(*node_id)
+                .unwrap_or_default()
+                .into_iter()
+                .chain(trane.get_starting_lessons(*node_id).unwrap_or_default())
+                .filter(|dependent| units.contains(dependent))
+                .collect();
+            dependents.sort();
+            dependents.dedup();
+            for dependent in dependents {
+                lines.push(format!("    \"{node_id}\" -> \"{dependent}\""));
+            }
+        }
+        lines.push("}\n".to_string());
+        Ok(lines.join("\n"))
+    }
+
+    /// Prints the manifest and the raw assets of the given exercise, or the current exercise if
+    /// the ID is empty.
+    pub fn dump_exercise(&self, exercise_id: Ustr) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let exercise_id = self.exercise_id_or_current(exercise_id)?;
+        let manifest = self.get_exercise_manifest(exercise_id)?;
+        dump_exercise(&manifest)
+    }
+
+    /// Prints the given exercise, or the current exercise if the ID is empty, through every display
+    /// mode so that the rendering can be compared.
+    pub fn render_exercise(&self, exercise_id: Ustr) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let exercise_id = self.exercise_id_or_current(exercise_id)?;
+        let manifest = self.get_exercise_manifest(exercise_id)?;
+        render_exercise(&manifest)
+    }
+
+    /// Writes the given unit IDs to a file, one per line.
+    fn write_unit_ids(path: &Path, unit_ids: &[Ustr]) -> Result<()> {
+        let mut file = File::create(path)
+            .with_context(|| format!("failed to create file at {}", path.display()))?;
+        for unit_id in unit_ids {
+            writeln!(file, "{unit_id}")?;
+        }
+        Ok(())
+    }
+
+    /// Reads the unit IDs from a file with one ID per line. Blank lines and lines starting with `#`
+    /// are ignored. Units that do not exist in the library are skipped with a warning.
+    fn read_unit_ids(&self, path: &Path) -> Result<Vec<Ustr>> {
+        let contents = read_to_string(path)
+            .with_context(|| format!("failed to read file at {}", path.display()))?;
+        let mut unit_ids = Vec::new();
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let unit_id = Ustr::from(line);
+            if self.unit_exists(unit_id)? {
+                unit_ids.push(unit_id);
+            } else {
+                print_warning(&format!(
+                    "skipping unit {unit_id} because it does not exist"
+                ));
+            }
+        }
+        Ok(unit_ids)
+    }
+
+    /// Writes all the entries in the blacklist to the given file, one per line. Returns the number
+    /// of entries written.
+    pub fn export_blacklist(&self, path: &Path) -> Result<usize> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let entries = self.trane.as_ref().unwrap().get_blacklist_entries()?;
+        Self::write_unit_ids(path, &entries)?;
+        Ok(entries.len())
+    }
+
+    /// Adds all the units listed in the given file to the blacklist. Units already in the blacklist
+    /// are left as they are. Returns the number of units imported.
+    pub fn import_blacklist(&mut self, path: &Path) -> Result<usize> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        let unit_ids = self.read_unit_ids(path)?;
+        self.blacklist_units(&unit_ids)?;
+        Ok(unit_ids.len())
+    }
+
+    /// Returns the given value as a CSV field, quoting it if needed.
+    fn csv_field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    /// Writes every trial of every exercise in the library to the given file in the given format.
+    /// The rows are written as the trials are read, so the whole history is never held in memory.
+    /// Timestamps are written in ISO 8601 format in UTC. If `include_notes` is true, the notes
+    /// about each exercise are added to its rows. Returns the number of trials written.
+    pub fn export_scores(
+        &self,
+        path: &Path,
+        format: ExportFormat,
+        include_notes: bool,
+    ) -> Result<usize> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let trane = self.trane.as_ref().unwrap();
+        let file = File::create(path)
+            .with_context(|| format!("failed to create file at {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        match (format, include_notes) {
+            (ExportFormat::Csv, false) => writeln!(writer, "exercise_id,timestamp,score")?,
+            (ExportFormat::Csv, true) => writeln!(writer, "exercise_id,timestamp,score,notes")?,
+            (ExportFormat::Json, _) => writeln!(writer, "[")?,
+        }
+
+        let exercise_ids = self.unit_ids.borrow().exercises.clone();
+        let notes = self.notes.as_ref().unwrap();
+        let mut num_trials = 0;
+        for exercise_id in exercise_ids {
+            let exercise_notes = notes.get_notes(exercise_id);
+            for trial in trane.get_scores(exercise_id, i64::MAX as usize)? {
+                let timestamp = Utc
+                    .timestamp_opt(trial.timestamp, 0)
+                    .single()
+                    .map(|dt| dt.to_rfc3339_opts(SecondsFormat::Secs, true))
+                    .unwrap_or_default();
+                match format {
+                    ExportFormat::Csv => {
+                        write!(
+                            writer,
+                            "{},{timestamp},{}",
+                            Self::csv_field(&exercise_id),
+                            trial.score
+                        )?;
+                        if include_notes {
+                            write!(writer, ",{}", Self::csv_field(&exercise_notes.join("; ")))?;
+                        }
+                        writeln!(writer)?;
+                    }
+                    ExportFormat::Json => {
+                        let mut row = json!({
+                            "exercise_id": exercise_id,
+                            "timestamp": timestamp,
+                            "score": trial.score,
+                        });
+                        if include_notes {
+                            row["notes"] = json!(exercise_notes);
+                        }
+                        let separator = if num_trials == 0 { "" } else { ",\n" };
+                        write!(writer, "{separator}  {row}")?;
+                    }
+                }
+                num_trials += 1;
+            }
+        }
+
+        if format == ExportFormat::Json {
+            if num_trials > 0 {
+                writeln!(writer)?;
+            }
+            writeln!(writer, "]")?;
+        }
+        writer.flush()?;
+        Ok(num_trials)
+    }
+
+    /// Writes all the entries in the review list to the given file, one per line. Returns the
+    /// number of entries written.
+    pub fn export_review_list(&self, path: &Path) -> Result<usize> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let entries = self.trane.as_ref().unwrap().get_review_list_entries()?;
+        Self::write_unit_ids(path, &entries)?;
+        Ok(entries.len())
+    }
+
+    /// Adds all the units listed in the given file to the review list. Units already in the review
+    /// list are left as they are. Returns the number of units imported.
+    pub fn import_review_list(&mut self, path: &Path) -> Result<usize> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        let unit_ids = self.read_unit_ids(path)?;
+        self.add_to_review_list(&unit_ids)?;
+        Ok(unit_ids.len())
+    }
+
     /// Filters out any empty ID from the given list.
     fn filter_empty_ids(ids: &[Ustr]) -> Vec<Ustr> {
         ids.iter().filter(|id| !id.is_empty()).copied().collect()
     }
 
-    /// Sets the filter to only show exercises from the given courses.
-    pub fn filter_courses(&mut self, course_ids: &[Ustr]) -> Result<()> {
+    /// Lets the user pick a unit from the open library interactively. Only units of the given type
+    /// are listed, or all units if no type is given. Returns an error if the user cancels.
+    fn pick_unit_id(&self, unit_type: Option<&UnitType>) -> Result<Ustr> {
+        let unit_ids = self.unit_ids.borrow();
+        let (description, candidates): (&str, Vec<Ustr>) = match unit_type {
+            Some(UnitType::Course) => ("a course", unit_ids.courses.clone()),
+            Some(UnitType::Lesson) => ("a lesson", unit_ids.lessons.clone()),
+            Some(UnitType::Exercise) => ("an exercise", unit_ids.exercises.clone()),
+            None => (
+                "a unit",
+                unit_ids
+                    .courses
+                    .iter()
+                    .chain(&unit_ids.lessons)
+                    .chain(&unit_ids.exercises)
+                    .copied()
+                    .collect(),
+            ),
+        };
+        pick_unit(description, &candidates)?.ok_or_else(|| anyhow!("no unit was selected"))
+    }
+
+    /// Returns the given unit ID, or lets the user pick one of the given type interactively if the
+    /// ID is empty or equal to `?`.
+    fn unit_id_or_pick(&self, unit_id: Ustr, unit_type: Option<&UnitType>) -> Result<Ustr> {
+        if unit_id.is_empty() || unit_id == PICK_UNIT_ID {
+            self.pick_unit_id(unit_type)
+        } else {
+            Ok(unit_id)
+        }
+    }
+
+    /// Sorts the given units so that each one comes after its dependencies among them. Ties are
+    /// broken by ID so that the order is deterministic. Units in a dependency cycle are added in
+    /// order of their IDs once no other unit can be placed.
+    fn dependency_order(&self, unit_ids: &[Ustr]) -> Vec<Ustr> {
+        let trane = self.trane.as_ref().unwrap();
+        let mut remaining: BTreeSet<Ustr> = unit_ids.iter().copied().collect();
+        let mut ordered = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let next = remaining
+                .iter()
+                .find(|unit_id| {
+                    trane
+                        .get_dependencies(**unit_id)
+                        .unwrap_or_default()
+                        .iter()
+                        .all(|dependency_id| !remaining.contains(dependency_id))
+                })
+                .or_else(|| remaining.first())
+                .copied()
+                .unwrap();
+            remaining.remove(&next);
+            ordered.push(next);
+        }
+        ordered
+    }
+
+    /// Returns the exercises in the given courses in the order in which they should be practiced
+    /// linearly. Courses and the lessons in each course are sorted by their dependencies, and the
+    /// exercises in each lesson by their IDs. Blacklisted exercises are kept in the order and
+    /// skipped when each batch is built, so that changes to the blacklist are picked up.
+    fn linear_exercises(&self, course_ids: &[Ustr]) -> Result<Vec<Ustr>> {
+        let trane = self.trane.as_ref().unwrap();
+        let mut exercise_ids = Vec::new();
+        let mut num_allowed = 0;
+        for course_id in self.dependency_order(course_ids) {
+            let lesson_ids = trane.get_lesson_ids(course_id).unwrap_or_default();
+            for lesson_id in self.dependency_order(&lesson_ids) {
+                let mut lesson_exercises = trane.get_exercise_ids(lesson_id).unwrap_or_default();
+                lesson_exercises.sort();
+                for exercise_id in lesson_exercises {
+                    if !self.exercise_blacklisted(exercise_id)? {
+                        num_allowed += 1;
+                    }
+                    exercise_ids.push(exercise_id);
+                }
+            }
+        }
+        ensure!(
+            num_allowed > 0,
+            "the given courses have no exercises outside the blacklist"
+        );
+        Ok(exercise_ids)
+    }
+
+    /// Sets the filter to only show exercises from the given courses. If no IDs are given, or if
+    /// any of them is `?`, the user picks a course interactively to use in its place. If `linear`
+    /// is true, the scheduler is bypassed and the exercises are shown one after the other, lesson
+    /// by lesson, in the order of their dependencies.
+    pub fn filter_courses(&mut self, course_ids: &[Ustr], linear: bool) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
-        let course_ids = Self::filter_empty_ids(course_ids);
+        let mut course_ids = Self::filter_empty_ids(course_ids);
+        if course_ids.is_empty() {
+            course_ids.push(PICK_UNIT_ID.into());
+        }
+        let course_ids = course_ids
+            .into_iter()
+            .map(|course_id| self.unit_id_or_pick(course_id, Some(&UnitType::Course)))
+            .collect::<Result<Vec<_>>>()?;
         for course_id in &course_ids {
             let unit_type = self.get_unit_type(*course_id)?;
             if unit_type != UnitType::Course {
@@ -291,8 +1077,15 @@ impl TraneApp {
             }
         }
 
-        self.filter = Some(UnitFilter::CourseFilter { course_ids });
+        let linear_exercise_ids = if linear {
+            Some(self.linear_exercises(&course_ids)?)
+        } else {
+            None
+        };
+
+        self.replace_filter(Some(UnitFilter::CourseFilter { course_ids }));
         self.reset_batch();
+        self.linear_exercise_ids = linear_exercise_ids;
         Ok(())
     }
 
@@ -308,57 +1101,131 @@ impl TraneApp {
             }
         }
 
-        self.filter = Some(UnitFilter::LessonFilter { lesson_ids });
+        self.replace_filter(Some(UnitFilter::LessonFilter { lesson_ids }));
         self.reset_batch();
         Ok(())
     }
 
-    /// Sets the filter to only show exercises which belong to any course or lesson with the given
-    /// metadata.
-    pub fn filter_metadata(
-        &mut self,
-        filter_op: FilterOp,
-        lesson_metadata: &Option<Vec<KeyValue>>,
-        course_metadata: &Option<Vec<KeyValue>>,
-    ) {
-        let basic_lesson_filters: Vec<_> = lesson_metadata
-            .as_ref()
+    /// Sets the filter to only show the given exercises.
+    pub fn filter_exercises(&mut self, exercise_ids: &[Ustr]) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let exercise_ids = Self::filter_empty_ids(exercise_ids);
+        ensure!(!exercise_ids.is_empty(), "no exercise IDs were given");
+        let mut lesson_ids = Vec::new();
+        for exercise_id in &exercise_ids {
+            let unit_type = self.get_unit_type(*exercise_id)?;
+            if unit_type != UnitType::Exercise {
+                bail!("Unit with ID {exercise_id} is not an exercise");
+            }
+            let lesson_id = self
+                .trane
+                .as_ref()
+                .unwrap()
+                .get_exercise_manifest(*exercise_id)
+                .ok_or_else(|| anyhow!("missing manifest for exercise {exercise_id}"))?
+                .lesson_id;
+            if !lesson_ids.contains(&lesson_id) {
+                lesson_ids.push(lesson_id);
+            }
+        }
+
+        self.replace_filter(Some(UnitFilter::LessonFilter { lesson_ids }));
+        self.filter_exercise_ids = Some(exercise_ids);
+        self.reset_batch();
+        Ok(())
+    }
+
+    /// Returns a basic filter on lesson metadata of the given type for each of the given pairs.
+    fn lesson_metadata_filters(
+        pairs: Option<&Vec<KeyValue>>,
+        filter_type: &FilterType,
+    ) -> Vec<KeyValueFilter> {
+        pairs
             .map(|pairs| {
                 pairs
                     .iter()
                     .map(|pair| KeyValueFilter::LessonFilter {
                         key: pair.key.clone(),
                         value: pair.value.clone(),
-                        filter_type: FilterType::Include,
+                        filter_type: filter_type.clone(),
                     })
                     .collect()
             })
-            .unwrap_or_default();
+            .unwrap_or_default()
+    }
 
-        let basic_course_filters: Vec<_> = course_metadata
-            .as_ref()
+    /// Returns a basic filter on course metadata of the given type for each of the given pairs.
+    fn course_metadata_filters(
+        pairs: Option<&Vec<KeyValue>>,
+        filter_type: &FilterType,
+    ) -> Vec<KeyValueFilter> {
+        pairs
             .map(|pairs| {
                 pairs
                     .iter()
                     .map(|pair| KeyValueFilter::CourseFilter {
                         key: pair.key.clone(),
                         value: pair.value.clone(),
-                        filter_type: FilterType::Include,
+                        filter_type: filter_type.clone(),
                     })
                     .collect()
             })
-            .unwrap_or_default();
+            .unwrap_or_default()
+    }
 
-        self.filter = Some(UnitFilter::MetadataFilter {
-            filter: KeyValueFilter::CombinedFilter {
+    /// Sets the filter to only show exercises which belong to any course or lesson with the given
+    /// metadata. The filter operation combines the included key-value pairs. Units which match any
+    /// of the excluded pairs are always left out, regardless of the operation.
+    pub fn filter_metadata(
+        &mut self,
+        filter_op: FilterOp,
+        lesson_metadata: &Option<Vec<KeyValue>>,
+        course_metadata: &Option<Vec<KeyValue>>,
+        exclude_lesson_metadata: &Option<Vec<KeyValue>>,
+        exclude_course_metadata: &Option<Vec<KeyValue>>,
+    ) {
+        let include_filters: Vec<_> =
+            Self::lesson_metadata_filters(lesson_metadata.as_ref(), &FilterType::Include)
+                .into_iter()
+                .chain(Self::course_metadata_filters(
+                    course_metadata.as_ref(),
+                    &FilterType::Include,
+                ))
+                .collect();
+        let exclude_filters: Vec<_> =
+            Self::lesson_metadata_filters(exclude_lesson_metadata.as_ref(), &FilterType::Exclude)
+                .into_iter()
+                .chain(Self::course_metadata_filters(
+                    exclude_course_metadata.as_ref(),
+                    &FilterType::Exclude,
+                ))
+                .collect();
+
+        // A unit passes an exclude filter if it does not have the pair, so the exclude filters
+        // must all pass. They are combined with the include filters using the `All` operation so
+        // that `Any` only applies to the included pairs.
+        let filter = match (include_filters.is_empty(), exclude_filters.is_empty()) {
+            (_, true) => KeyValueFilter::CombinedFilter {
                 op: filter_op,
-                filters: basic_lesson_filters
-                    .iter()
-                    .chain(basic_course_filters.iter())
-                    .cloned()
-                    .collect(),
+                filters: include_filters,
             },
-        });
+            (true, false) => KeyValueFilter::CombinedFilter {
+                op: FilterOp::All,
+                filters: exclude_filters,
+            },
+            (false, false) => KeyValueFilter::CombinedFilter {
+                op: FilterOp::All,
+                filters: std::iter::once(KeyValueFilter::CombinedFilter {
+                    op: filter_op,
+                    filters: include_filters,
+                })
+                .chain(exclude_filters)
+                .collect(),
+            },
+        };
+
+        self.replace_filter(Some(UnitFilter::MetadataFilter { filter }));
         self.reset_batch();
     }
 
@@ -366,7 +1233,7 @@ impl TraneApp {
     pub fn filter_review_list(&mut self) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
-        self.filter = Some(UnitFilter::ReviewListFilter);
+        self.replace_filter(Some(UnitFilter::ReviewListFilter));
         self.reset_batch();
         Ok(())
     }
@@ -377,17 +1244,49 @@ impl TraneApp {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
         let unit_ids = Self::filter_empty_ids(unit_ids);
-        self.filter = Some(UnitFilter::Dependencies { unit_ids, depth });
+        self.replace_filter(Some(UnitFilter::Dependencies { unit_ids, depth }));
         self.reset_batch();
         Ok(())
     }
 
-    /// Sets the filter to only show exercises from the given units and their dependents.
-    pub fn filter_dependents(&mut self, unit_ids: &[Ustr]) -> Result<()> {
+    /// Returns the lessons in the given units and in their dependents up to the given depth. All
+    /// the lessons of the courses that are found are included.
+    fn dependent_lessons(&self, unit_ids: &[Ustr], depth: usize) -> Result<Vec<Ustr>> {
+        let trane = self.trane.as_ref().unwrap();
+        let visited = Self::reachable_units(unit_ids, depth, |unit_id| {
+            trane.get_dependents(unit_id).unwrap_or_default()
+        });
+
+        let mut lesson_ids = BTreeSet::new();
+        for unit_id in visited {
+            match self.get_unit_type(unit_id)? {
+                UnitType::Course => {
+                    lesson_ids.extend(trane.get_lesson_ids(unit_id).unwrap_or_default());
+                }
+                UnitType::Lesson => {
+                    lesson_ids.insert(unit_id);
+                }
+                UnitType::Exercise => bail!("unit with ID {unit_id} is not a course or lesson"),
+            }
+        }
+        Ok(lesson_ids.into_iter().collect())
+    }
+
+    /// Sets the filter to only show exercises from the given units and their dependents. If a
+    /// depth is given, only the dependents up to that depth are included. Trane's dependents
+    /// filter has no depth, so in that case the filter is set to the lessons of all the units
+    /// found instead.
+    pub fn filter_dependents(&mut self, unit_ids: &[Ustr], depth: Option<usize>) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
         let unit_ids = Self::filter_empty_ids(unit_ids);
-        self.filter = Some(UnitFilter::Dependents { unit_ids });
+        let filter = match depth {
+            None => UnitFilter::Dependents { unit_ids },
+            Some(depth) => UnitFilter::LessonFilter {
+                lesson_ids: self.dependent_lessons(&unit_ids, depth)?,
+            },
+        };
+        self.replace_filter(Some(filter));
         self.reset_batch();
         Ok(())
     }
@@ -415,28 +1314,292 @@ impl TraneApp {
         }
 
         println!("Saved unit filters:");
-        println!("{:<30} {:<50}", "ID", "Description");
+        let id_width = column_width("ID", filters.iter().map(|filter| filter.0.as_str()), 1, 30);
+        println!("{:<id_width$} Description", "ID");
         for filter in filters {
-            println!("{:<30} {:<50}", filter.0, filter.1);
+            println!("{:<id_width$} {}", filter.0, filter.1);
+        }
+        Ok(())
+    }
+
+    /// Saves the current unit filter with the given ID and description. Trane only reads the saved
+    /// filters when the library is opened, so the Trane instance is reloaded afterwards. The
+    /// scheduler options are carried over to the new instance.
+    pub fn save_filter(&mut self, id: &str, description: &str) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        let Some(filter) = self.filter.clone() else {
+            bail!("no filter is set");
+        };
+        ensure!(
+            self.filter_exercise_ids.is_none(),
+            "filters on individual exercises cannot be saved"
+        );
+        ensure!(
+            !id.is_empty() && !id.contains(['/', '\\']),
+            "invalid filter ID {id}"
+        );
+        let trane = self.trane.as_ref().unwrap();
+        ensure!(
+            trane.get_filter(id).is_none(),
+            "a filter with ID {id} already exists"
+        );
+
+        // Write the filter to the directory of saved filters.
+        let library_root = trane.library_root();
+        let path = Path::new(&library_root)
+            .join(TRANE_CONFIG_DIR_PATH)
+            .join(FILTERS_DIR)
+            .join(format!("{id}.json"));
+        let saved_filter = SavedFilter {
+            id: id.to_string(),
+            description: description.to_string(),
+            filter,
+        };
+        write_json(&path, &saved_filter, "filter")?;
+
+        // Reload the Trane instance so that the new filter can be selected.
+        let scheduler_options = trane.get_scheduler_options();
+        self.save_mantra_count()?;
+        let mut trane = Trane::new_local(&std::env::current_dir()?, Path::new(&library_root))?;
+        trane.set_scheduler_options(scheduler_options);
+        self.trane = Some(trane);
+        self.saved_mantra_count = 0;
+        Ok(())
+    }
+
+    /// Returns the IDs of the exercises in the given unit of the given type. An exercise contains
+    /// only itself.
+    fn unit_exercise_ids(&self, unit_id: Ustr, unit_type: &UnitType) -> Vec<Ustr> {
+        let trane = self.trane.as_ref().unwrap();
+        match unit_type {
+            UnitType::Exercise => vec![unit_id],
+            UnitType::Lesson => trane.get_exercise_ids(unit_id).unwrap_or_default(),
+            UnitType::Course => trane
+                .get_lesson_ids(unit_id)
+                .unwrap_or_default()
+                .into_iter()
+                .flat_map(|lesson_id| trane.get_exercise_ids(lesson_id).unwrap_or_default())
+                .collect(),
+        }
+    }
+
+    /// Returns the number of exercises in the given unit, how many of them have been practiced, and
+    /// their average score, or `None` if none of them have any scores.
+    fn unit_progress(&self, unit_id: Ustr) -> Result<(usize, usize, Option<f32>)> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let trane = self.trane.as_ref().unwrap();
+        let exercise_ids = self.unit_exercise_ids(unit_id, &self.get_unit_type(unit_id)?);
+
+        let num_trials = trane.get_scheduler_options().num_trials;
+        let scorer = SimpleScorer {};
+        let mut total = 0.0;
+        let mut num_scored = 0_usize;
+        for exercise_id in &exercise_ids {
+            let trials = trane.get_scores(*exercise_id, num_trials)?;
+            if trials.is_empty() {
+                continue;
+            }
+            total += scorer.score(&trials)?;
+            num_scored += 1;
+        }
+
+        if num_scored == 0 {
+            return Ok((exercise_ids.len(), 0, None));
+        }
+        Ok((
+            exercise_ids.len(),
+            num_scored,
+            Some(total / num_scored as f32),
+        ))
+    }
+
+    /// Returns the average score of the exercises in the given unit that have been practiced, or
+    /// `None` if none of them have any scores.
+    fn unit_mastery(&self, unit_id: Ustr) -> Result<Option<f32>> {
+        Ok(self.unit_progress(unit_id)?.2)
+    }
+
+    /// Prints the ID of the given units along with the fraction of their exercises that have been
+    /// scored and their average score.
+    fn print_units_progress(&self, unit_ids: &[Ustr]) -> Result<()> {
+        if self.output_format == OutputFormat::Json {
+            let mut units = Vec::new();
+            for unit_id in unit_ids {
+                let (num_exercises, num_scored, average) = self.unit_progress(*unit_id)?;
+                units.push(json!({
+                    "unit_type": self.get_unit_type(*unit_id)?.to_string(),
+                    "unit_id": unit_id,
+                    "num_exercises": num_exercises,
+                    "num_scored": num_scored,
+                    "average_score": average,
+                }));
+            }
+            return print_json(&json!(units));
+        }
+
+        let mut pager = Pager::new();
+        let id_width = column_width("Unit ID", unit_ids.iter().map(Ustr::as_str), 30, 50);
+        pager.println(&format!(
+            "{:<id_width$} {:>9} {:>9} {:>9}",
+            "Unit ID", "Scored", "Progress", "Avg Score"
+        ))?;
+        for unit_id in unit_ids {
+            let (num_exercises, num_scored, average) = self.unit_progress(*unit_id)?;
+            let progress = if num_exercises == 0 {
+                "n/a".to_string()
+            } else {
+                format!("{:.0}%", 100.0 * num_scored as f32 / num_exercises as f32)
+            };
+            let average = average.map_or("n/a".to_string(), |average| format!("{average:.2}"));
+            if !pager.println(&format!(
+                "{:<id_width$} {:>9} {progress:>9} {average:>9}",
+                unit_id.as_str(),
+                format!("{num_scored}/{num_exercises}"),
+            ))? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints the ID of the given exercises along with their most recent score and when it was
+    /// recorded. Exercises that were never scored are printed first so that gaps are easy to spot.
+    fn print_exercises_scores(&self, exercise_ids: &[Ustr]) -> Result<()> {
+        let trane = self.trane.as_ref().unwrap();
+        let mut exercises = Vec::with_capacity(exercise_ids.len());
+        for exercise_id in exercise_ids {
+            let last_trial = trane.get_scores(*exercise_id, 1)?.into_iter().next();
+            exercises.push((*exercise_id, last_trial));
+        }
+        exercises.sort_by_key(|(_, last_trial)| last_trial.is_some());
+
+        if self.output_format == OutputFormat::Json {
+            let exercises: Vec<_> = exercises
+                .iter()
+                .map(|(exercise_id, last_trial)| {
+                    json!({
+                        "exercise_id": exercise_id,
+                        "last_score": last_trial.as_ref().map(|trial| trial.score),
+                        "timestamp": last_trial.as_ref().map(|trial| trial.timestamp),
+                    })
+                })
+                .collect();
+            return print_json(&json!(exercises));
+        }
+
+        let now = Utc::now().timestamp();
+        let mut pager = Pager::new();
+        let id_width = column_width(
+            "Exercise ID",
+            exercises
+                .iter()
+                .map(|(exercise_id, _)| exercise_id.as_str()),
+            29,
+            50,
+        );
+        pager.println(&format!(
+            "{:<id_width$} {:>10}  When",
+            "Exercise ID", "Last Score"
+        ))?;
+        for (exercise_id, last_trial) in exercises {
+            let line = match last_trial {
+                None => format!("{:<id_width$} {:>10}", exercise_id.as_str(), "unscored"),
+                Some(trial) => format!(
+                    "{:<id_width$} {}  {}",
+                    exercise_id.as_str(),
+                    color_by_score(&format!("{:>10}", trial.score as u8), trial.score, false),
+                    relative_time(trial.timestamp, now)
+                ),
+            };
+            if !pager.println(&line)? {
+                break;
+            }
         }
         Ok(())
     }
 
-    /// Prints the info of the given units to the terminal.
-    fn print_units_info(&self, unit_ids: &[Ustr]) -> Result<()> {
-        println!("{:<15} {:<50}", "Unit Type", "Unit ID");
+    /// Prints the info of the given units to the terminal. If `show_mastery` is true, a badge with
+    /// the aggregate mastery of each unit is printed as well.
+    fn print_units_info(&self, unit_ids: &[Ustr], show_mastery: bool) -> Result<()> {
+        let mut pager = Pager::new();
+        if !show_mastery {
+            pager.println(&format!("{:<15} Unit ID", "Unit Type"))?;
+            for unit_id in unit_ids {
+                let unit_type = self.get_unit_type(*unit_id)?;
+                if !pager.println(&format!("{unit_type:<15} {unit_id}"))? {
+                    break;
+                }
+            }
+            return Ok(());
+        }
+
+        let passing_score = self
+            .trane
+            .as_ref()
+            .unwrap()
+            .get_scheduler_options()
+            .passing_score
+            .compute_score(0);
+        let id_width = column_width("Unit ID", unit_ids.iter().map(Ustr::as_str), 32, 50);
+        pager.println(&format!(
+            "{:<15} {:<id_width$} Mastery",
+            "Unit Type", "Unit ID"
+        ))?;
         for unit_id in unit_ids {
             let unit_type = self.get_unit_type(*unit_id)?;
-            println!("{unit_type:<15} {unit_id:<50}");
+            let badge = mastery_badge(self.unit_mastery(*unit_id)?, passing_score);
+            if !pager.println(&format!(
+                "{:<15} {:<id_width$} {badge}",
+                unit_type,
+                unit_id.as_str()
+            ))? {
+                break;
+            }
         }
         Ok(())
     }
 
-    /// Lists the IDs of all the courses in the library.
-    pub fn list_courses(&self) -> Result<()> {
+    /// Prints the type and ID of the given units as a JSON array. If `show_mastery` is true, the
+    /// aggregate mastery score of each unit is included as well.
+    fn print_units_json(&self, unit_ids: &[Ustr], show_mastery: bool) -> Result<()> {
+        let mut units = Vec::new();
+        for unit_id in unit_ids {
+            let mut unit = json!({
+                "unit_type": self.get_unit_type(*unit_id)?.to_string(),
+                "unit_id": unit_id,
+            });
+            if show_mastery {
+                unit["mastery"] = json!(self.unit_mastery(*unit_id)?);
+            }
+            units.push(unit);
+        }
+        print_json(&json!(units))
+    }
+
+    /// Lists the IDs of all the courses in the library, or only their number if `show_count` is
+    /// true.
+    pub fn list_courses(
+        &self,
+        show_mastery: bool,
+        show_progress: bool,
+        show_count: bool,
+    ) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
         let courses = self.trane.as_ref().unwrap().get_course_ids();
+        if show_count {
+            println!("{}", courses.len());
+            return Ok(());
+        }
+        if show_progress && self.output_format == OutputFormat::Json {
+            return self.print_units_progress(&courses);
+        }
+        if self.output_format == OutputFormat::Json {
+            return self.print_units_json(&courses, show_mastery);
+        }
         if courses.is_empty() {
             println!("No courses in library");
             return Ok(());
@@ -444,12 +1607,21 @@ impl TraneApp {
 
         println!("Courses:");
         println!();
-        self.print_units_info(&courses)?;
+        if show_progress {
+            self.print_units_progress(&courses)?;
+        } else {
+            self.print_units_info(&courses, show_mastery)?;
+        }
         Ok(())
     }
 
-    /// Lists the dependencies of the given unit.
-    pub fn list_dependencies(&self, unit_id: Ustr) -> Result<()> {
+    /// Lists the dependencies of the given unit, or only their number if `show_count` is true.
+    pub fn list_dependencies(
+        &self,
+        unit_id: Ustr,
+        show_mastery: bool,
+        show_count: bool,
+    ) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
         let unit_type = self.get_unit_type(unit_id)?;
@@ -462,7 +1634,16 @@ impl TraneApp {
             .as_ref()
             .unwrap()
             .get_dependencies(unit_id)
-            .unwrap_or_default();
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<_>>();
+        if show_count {
+            println!("{}", dependencies.len());
+            return Ok(());
+        }
+        if self.output_format == OutputFormat::Json {
+            return self.print_units_json(&dependencies, show_mastery);
+        }
         if dependencies.is_empty() {
             println!("No dependencies for unit with ID {unit_id}");
             return Ok(());
@@ -470,12 +1651,17 @@ impl TraneApp {
 
         println!("Dependencies:");
         println!();
-        self.print_units_info(&dependencies.iter().copied().collect::<Vec<_>>())?;
+        self.print_units_info(&dependencies, show_mastery)?;
         Ok(())
     }
 
-    /// Lists the dependents of the given unit.
-    pub fn list_dependents(&self, unit_id: Ustr) -> Result<()> {
+    /// Lists the dependents of the given unit, or only their number if `show_count` is true.
+    pub fn list_dependents(
+        &self,
+        unit_id: Ustr,
+        show_mastery: bool,
+        show_count: bool,
+    ) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
         let unit_type = self.get_unit_type(unit_id)?;
@@ -488,7 +1674,16 @@ impl TraneApp {
             .as_ref()
             .unwrap()
             .get_dependents(unit_id)
-            .unwrap_or_default();
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<_>>();
+        if show_count {
+            println!("{}", dependents.len());
+            return Ok(());
+        }
+        if self.output_format == OutputFormat::Json {
+            return self.print_units_json(&dependents, show_mastery);
+        }
         if dependents.is_empty() {
             println!("No dependents for unit with ID {unit_id}");
             return Ok(());
@@ -496,12 +1691,19 @@ impl TraneApp {
 
         println!("Dependents:");
         println!();
-        self.print_units_info(&dependents.iter().copied().collect::<Vec<_>>())?;
+        self.print_units_info(&dependents, show_mastery)?;
         Ok(())
     }
 
-    /// Lists the IDs of all the exercises in the given lesson.
-    pub fn list_exercises(&self, lesson_id: Ustr) -> Result<()> {
+    /// Lists the IDs of all the exercises in the given lesson, or only their number if `show_count`
+    /// is true. If `show_scores` is true, the most recent score of each exercise is shown as well.
+    pub fn list_exercises(
+        &self,
+        lesson_id: Ustr,
+        show_mastery: bool,
+        show_count: bool,
+        show_scores: bool,
+    ) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
         let exercises = self
@@ -510,6 +1712,16 @@ impl TraneApp {
             .unwrap()
             .get_exercise_ids(lesson_id)
             .unwrap_or_default();
+        if show_count {
+            println!("{}", exercises.len());
+            return Ok(());
+        }
+        if show_scores && self.output_format == OutputFormat::Json {
+            return self.print_exercises_scores(&exercises);
+        }
+        if self.output_format == OutputFormat::Json {
+            return self.print_units_json(&exercises, show_mastery);
+        }
         if exercises.is_empty() {
             println!("No exercises in lesson {lesson_id}");
             return Ok(());
@@ -517,12 +1729,23 @@ impl TraneApp {
 
         println!("Exercises:");
         println!();
-        self.print_units_info(&exercises)?;
+        if show_scores {
+            self.print_exercises_scores(&exercises)?;
+        } else {
+            self.print_units_info(&exercises, show_mastery)?;
+        }
         Ok(())
     }
 
-    /// Lists the IDs of all the lessons in the given course.
-    pub fn list_lessons(&self, course_id: Ustr) -> Result<()> {
+    /// Lists the IDs of all the lessons in the given course, or only their number if `show_count` is
+    /// true.
+    pub fn list_lessons(
+        &self,
+        course_id: Ustr,
+        show_mastery: bool,
+        show_progress: bool,
+        show_count: bool,
+    ) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
         let lessons = self
@@ -531,6 +1754,16 @@ impl TraneApp {
             .unwrap()
             .get_lesson_ids(course_id)
             .unwrap_or_default();
+        if show_count {
+            println!("{}", lessons.len());
+            return Ok(());
+        }
+        if show_progress && self.output_format == OutputFormat::Json {
+            return self.print_units_progress(&lessons);
+        }
+        if self.output_format == OutputFormat::Json {
+            return self.print_units_json(&lessons, show_mastery);
+        }
         if lessons.is_empty() {
             println!("No lessons in course {course_id}");
             return Ok(());
@@ -538,7 +1771,11 @@ impl TraneApp {
 
         println!("Lessons:");
         println!();
-        self.print_units_info(&lessons)?;
+        if show_progress {
+            self.print_units_progress(&lessons)?;
+        } else {
+            self.print_units_info(&lessons, show_mastery)?;
+        }
         Ok(())
     }
 
@@ -680,115 +1917,719 @@ impl TraneApp {
         self.submit_current_score()?;
 
         self.current_score = None;
-        self.batch_index += 1;
-        if self.batch.is_empty() || self.batch_index >= self.batch.len() {
-            self.batch = self
-                .trane
-                .as_ref()
-                .unwrap()
-                .get_exercise_batch(self.exercise_filter())?;
-            self.batch_index = 0;
+        self.batch_reset = false;
+        self.advance_batch()?;
+
+        // Skip exercises that were shown recently, but give up after a few attempts so that small
+        // libraries or narrow filters still make progress.
+        let mut manifest = self.current_exercise()?;
+        for _ in 0..MAX_SKIPPED_REPEATS {
+            if !self.recent_exercises.contains(manifest.id) {
+                break;
+            }
+            self.advance_batch()?;
+            manifest = self.current_exercise()?;
         }
 
-        let manifest = self.current_exercise()?;
-        manifest.display_exercise()
+        // Skip exercises that cannot be displayed so that a broken exercise does not block the
+        // rest of the batch. Give up if too many fail in a row, as the whole library might be
+        // broken.
+        let mut num_broken = 0;
+        loop {
+            match self.display_exercise(&manifest) {
+                Ok(()) => break,
+                Err(err) if num_broken < MAX_SKIPPED_BROKEN => {
+                    print_warning(&format!(
+                        "skipped exercise {} because it could not be displayed: {err:#}",
+                        manifest.id
+                    ));
+                    num_broken += 1;
+                    self.advance_batch()?;
+                    manifest = self.current_exercise()?;
+                }
+                Err(err) => {
+                    return Err(err.context(format!(
+                        "gave up after {num_broken} exercises in a row could not be displayed"
+                    )))
+                }
+            }
+        }
+        self.recent_exercises.push(manifest.id);
+        Ok(())
     }
 
-    /// Opens the course library at the given path.
-    pub fn open_library(&mut self, library_root: &str) -> Result<()> {
-        let trane = Trane::new_local(&std::env::current_dir()?, Path::new(library_root))?;
-        self.trane = Some(trane);
-        self.batch.drain(..);
-        self.batch_index = 0;
-        Ok(())
+    /// Submits the score for the current exercise and displays the exercise at the given index of
+    /// the current batch, without requesting a new batch.
+    fn move_in_batch(&mut self, index: usize) -> Result<()> {
+        self.submit_current_score()?;
+        self.current_score = None;
+        self.batch_index = index;
+        let manifest = self.current_exercise()?;
+        self.display_exercise(&manifest)
     }
 
-    /// Assigns the given score to the current exercise.
-    pub fn record_score(&mut self, score: u8) -> Result<()> {
+    /// Displays the previous exercise in the current batch.
+    pub fn prev(&mut self) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
-
-        let mastery_score = match score {
-            1 => Ok(MasteryScore::One),
-            2 => Ok(MasteryScore::Two),
-            3 => Ok(MasteryScore::Three),
-            4 => Ok(MasteryScore::Four),
-            5 => Ok(MasteryScore::Five),
-            _ => Err(anyhow!("invalid score {}", score)),
-        }?;
-        self.current_score = Some(mastery_score);
-        Ok(())
+        ensure!(!self.batch.is_empty(), "the batch is empty");
+        ensure!(
+            self.batch_index > 0,
+            "already at the first exercise in the batch"
+        );
+        self.move_in_batch(self.batch_index - 1)
     }
 
-    /// Sets the unit filter to the saved filter with the given ID. Setting a filter resets the
-    /// study session, as only one of the two can be active at a time.
-    pub fn set_filter(&mut self, filter_id: &str) -> Result<()> {
+    /// Displays the exercise at the given position of the current batch, starting at one.
+    pub fn goto(&mut self, position: usize) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
+        ensure!(!self.batch.is_empty(), "the batch is empty");
+        ensure!(
+            (1..=self.batch.len()).contains(&position),
+            "position {position} is out of range, the batch has {} exercises",
+            self.batch.len()
+        );
+        self.move_in_batch(position - 1)
+    }
 
-        let saved_filter = self
-            .trane
-            .as_ref()
-            .unwrap()
-            .get_filter(filter_id)
-            .ok_or_else(|| anyhow!("no filter with ID {}", filter_id))?;
-        self.filter = Some(saved_filter.filter);
-        self.study_session = None;
-        self.reset_batch();
-        Ok(())
+    /// Requests a new batch of exercises and removes the exercises of suspended units from it. If
+    /// all the exercises in the batch are suspended, a new batch is requested a few times before
+    /// giving up, since the scheduler might pick other exercises.
+    fn new_batch(&self) -> Result<Vec<ExerciseManifest>> {
+        let Some(suspensions) = &self.suspensions else {
+            return self.scheduled_batch();
+        };
+        for _ in 0..MAX_SUSPENDED_BATCH_ATTEMPTS {
+            let mut batch = self.scheduled_batch()?;
+            if batch.is_empty() {
+                return Ok(batch);
+            }
+            let now = Utc::now().timestamp();
+            batch.retain(|manifest| !suspensions.is_suspended(manifest, now));
+            if !batch.is_empty() {
+                return Ok(batch);
+            }
+        }
+        bail!(
+            "all the exercises in the last {MAX_SUSPENDED_BATCH_ATTEMPTS} batches belong to \
+            suspended units, use suspend list to see them"
+        )
     }
 
-    /// Shows the answer to the current exercise.
-    pub fn show_answer(&mut self) -> Result<()> {
-        ensure!(self.trane.is_some(), "no Trane instance is open");
+    /// Requests a new batch of exercises from Trane. If the filter is restricted to individual
+    /// exercises, the batch only keeps those exercises. If the filter goes through courses
+    /// linearly, the batch contains all their exercises in order instead.
+    fn scheduled_batch(&self) -> Result<Vec<ExerciseManifest>> {
+        let trane = self.trane.as_ref().unwrap();
+        if self.linear_exercise_ids.is_some() {
+            return self.linear_batch();
+        }
+        let batch = trane.get_exercise_batch(self.exercise_filter())?;
+        let Some(exercise_ids) = &self.filter_exercise_ids else {
+            return Ok(batch);
+        };
 
-        let curr_exercise = self.current_exercise()?;
-        curr_exercise.display_answer()
-    }
+        let batch: Vec<ExerciseManifest> = batch
+            .into_iter()
+            .filter(|manifest| exercise_ids.contains(&manifest.id))
+            .collect();
+        if !batch.is_empty() {
+            return Ok(batch);
+        }
 
-    /// Lists all the entries in the blacklist.
-    pub fn list_blacklist(&self) -> Result<()> {
-        ensure!(self.trane.is_some(), "no Trane instance is open");
+        // The scheduler did not pick any of the exercises, so practice all of them instead.
+        Ok(exercise_ids
+            .iter()
+            .filter_map(|exercise_id| trane.get_exercise_manifest(*exercise_id))
+            .collect())
+    }
 
+    /// Returns the exercises in the linear order from the current position onwards, starting over
+    /// from the first exercise if none are left. Exercises blacklisted since the order was built
+    /// are left out.
+    fn linear_batch(&self) -> Result<Vec<ExerciseManifest>> {
         let trane = self.trane.as_ref().unwrap();
-        let entries = trane.get_blacklist_entries()?;
-        if entries.is_empty() {
-            println!("No entries in the blacklist");
-            return Ok(());
+        let exercise_ids = self.linear_exercise_ids.as_ref().unwrap();
+        for start in [self.linear_position, 0] {
+            let mut batch = Vec::new();
+            for exercise_id in exercise_ids.iter().skip(start) {
+                if self.exercise_blacklisted(*exercise_id)? {
+                    continue;
+                }
+                if let Some(manifest) = trane.get_exercise_manifest(*exercise_id) {
+                    batch.push(manifest);
+                }
+            }
+            if !batch.is_empty() {
+                return Ok(batch);
+            }
         }
+        bail!("all the exercises in the linear order are blacklisted")
+    }
 
-        println!("{:<15} Unit ID", "Unit Type");
-        for unit_id in entries {
-            let unit_type = if let Some(ut) = trane.get_unit_type(unit_id) {
-                ut.to_string()
-            } else {
-                "Unknown".to_string()
-            };
-            println!("{unit_type:<15} {unit_id}");
+    /// Moves to the next exercise in the batch, requesting a new batch if the current one is
+    /// exhausted.
+    fn advance_batch(&mut self) -> Result<()> {
+        self.batch_index += 1;
+        if self.batch.is_empty() || self.batch_index >= self.batch.len() {
+            // Once the end of the linear order is reached, the next batch starts over.
+            if !self.batch.is_empty() {
+                self.linear_position = 0;
+            }
+            self.batch = self.new_batch()?;
+            self.batch_index = 0;
         }
         Ok(())
     }
 
-    /// Shows the currently set filter.
-    pub fn show_filter(&self) {
-        if self.filter.is_none() {
-            println!("No filter is set");
-        } else {
-            println!("Filter:");
-            println!("{:#?}", self.filter.as_ref().unwrap());
-        }
+    /// Sets the number of recently shown exercises that the `next` command skips. A value of zero
+    /// disables the check.
+    pub fn set_recent_exercises(&mut self, count: usize) {
+        self.recent_exercises.set_capacity(count);
     }
 
-    /// Shows the course instructions for the given course.
-    pub fn show_course_instructions(&self, course_id: Ustr) -> Result<()> {
-        ensure!(self.trane.is_some(), "no Trane instance is open");
+    /// Sets the format in which commands print their results.
+    pub fn set_output_format(&mut self, output_format: OutputFormat) {
+        self.output_format = output_format;
+    }
 
-        let course_id = self.course_id_or_current(course_id)?;
-        let manifest = self
-            .trane
-            .as_ref()
-            .unwrap()
-            .get_course_manifest(course_id)
-            .ok_or_else(|| anyhow!("no manifest for course with ID {}", course_id))?;
-        match manifest.course_instructions {
+    /// Sets the action taken when Ctrl-C is pressed at the prompt.
+    pub fn set_interrupt_behavior(&mut self, behavior: InterruptBehavior) {
+        self.interrupt_behavior = behavior;
+    }
+
+    /// Returns the action taken when Ctrl-C is pressed at the prompt.
+    pub fn interrupt_behavior(&self) -> InterruptBehavior {
+        self.interrupt_behavior
+    }
+
+    /// Sets the batch size used for the libraries opened from now on whose preferences do not set
+    /// their own.
+    pub fn set_default_batch_size(&mut self, batch_size: usize) {
+        self.default_batch_size = Some(batch_size);
+    }
+
+    /// Sets the path to the history file shown by the `config show` command.
+    pub fn set_history_path(&mut self, history_path: &Path) {
+        self.history_path = Some(history_path.to_path_buf());
+    }
+
+    /// Returns the number of recently shown exercises that the `next` command skips.
+    pub fn recent_exercises(&self) -> usize {
+        self.recent_exercises.capacity()
+    }
+
+    /// Enters grade mode, in which single key presses are used to reveal the answer and score the
+    /// current exercise. Scoring an exercise moves on to the next one, just like running the
+    /// `score` and `next` commands.
+    pub fn grade(&mut self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        if self.current_exercise().is_ok() {
+            self.current()?;
+        } else {
+            self.next()?;
+        }
+
+        println!();
+        println!("Grade mode: press space to show the answer, 1-5 to score, or Esc to exit");
+        loop {
+            match read_grade_key()? {
+                GradeKey::Reveal => self.show_answer()?,
+                GradeKey::Score(score) => {
+                    self.record_score(score, None)?;
+                    println!("Recorded mastery score {score} for current exercise.");
+                    println!();
+                    self.next()?;
+                    println!();
+                    println!("Press space to show the answer, 1-5 to score, or Esc to exit");
+                }
+                GradeKey::Exit => break,
+            }
+        }
+        println!("Exited grade mode");
+        Ok(())
+    }
+
+    /// Returns the IDs of all the courses, lessons, and exercises in the library.
+    fn collect_unit_ids(trane: &Trane) -> UnitIds {
+        let courses = trane.get_course_ids();
+        let lessons: Vec<Ustr> = courses
+            .iter()
+            .flat_map(|course_id| trane.get_lesson_ids(*course_id).unwrap_or_default())
+            .collect();
+        let exercises = lessons
+            .iter()
+            .flat_map(|lesson_id| trane.get_exercise_ids(*lesson_id).unwrap_or_default())
+            .collect();
+        UnitIds {
+            courses,
+            lessons,
+            exercises,
+        }
+    }
+
+    /// Returns a handle to the IDs of the units in the open library, which is updated whenever a
+    /// library is opened.
+    pub fn unit_ids_handle(&self) -> SharedUnitIds {
+        Rc::clone(&self.unit_ids)
+    }
+
+    /// Saves the active filter or study session to the config directory of the open library, so
+    /// that it's restored the next time the library is opened. Nothing is saved in read-only mode.
+    pub fn save_active_filter(&self) -> Result<()> {
+        let Some(trane) = self.trane.as_ref() else {
+            return Ok(());
+        };
+        if self.read_only {
+            return Ok(());
+        }
+
+        let active_filter = match (&self.filter, &self.study_session) {
+            (Some(_), _) if self.filter_exercise_ids.is_some() => Some(ActiveFilter::Exercises(
+                self.filter_exercise_ids.clone().unwrap_or_default(),
+            )),
+            (Some(filter), _) => Some(ActiveFilter::Filter(filter.clone())),
+            (None, Some(study_session)) => Some(ActiveFilter::StudySession(study_session.clone())),
+            (None, None) => None,
+        };
+        let config_dir = Path::new(&trane.library_root()).join(TRANE_CONFIG_DIR_PATH);
+        ActiveFilter::save(active_filter.as_ref(), &config_dir)
+    }
+
+    /// Restores the filter or study session saved the last time the library was closed. A saved
+    /// filter that cannot be read or that references units that no longer exist is dropped with a
+    /// warning.
+    fn restore_active_filter(&mut self, config_dir: &Path) {
+        self.replace_filter(None);
+        self.study_session = None;
+        let active_filter = match ActiveFilter::load(config_dir) {
+            Ok(Some(active_filter)) => active_filter,
+            Ok(None) => return,
+            Err(err) => {
+                print_warning(&format!("dropped the saved filter: {err:#}"));
+                return;
+            }
+        };
+
+        let missing_ids: Vec<String> = active_filter
+            .unit_ids()
+            .into_iter()
+            .filter(|unit_id| !self.unit_exists(*unit_id).unwrap_or(false))
+            .map(|unit_id| unit_id.to_string())
+            .collect();
+        if !missing_ids.is_empty() {
+            print_warning(&format!(
+                "dropped the saved filter because these units no longer exist: {}",
+                missing_ids.join(", ")
+            ));
+            return;
+        }
+
+        match active_filter {
+            ActiveFilter::Filter(filter) => {
+                println!("Restored filter: {}", Self::describe_filter(&filter));
+                self.replace_filter(Some(filter));
+            }
+            ActiveFilter::Exercises(exercise_ids) => match self.filter_exercises(&exercise_ids) {
+                Ok(()) => println!("Restored filter: {} exercises", exercise_ids.len()),
+                Err(err) => print_warning(&format!("dropped the saved filter: {err:#}")),
+            },
+            ActiveFilter::StudySession(study_session) => {
+                println!("Restored study session: {}", study_session.definition.id);
+                self.study_session = Some(study_session);
+            }
+        }
+    }
+
+    /// Returns whether the given directory or any of its subdirectories contains a course manifest.
+    /// Hidden directories and symbolic links are not searched.
+    fn contains_course_manifest(dir: &Path) -> bool {
+        let Ok(entries) = read_dir(dir) else {
+            return false;
+        };
+        entries.flatten().any(|entry| {
+            let file_name = entry.file_name();
+            if file_name == COURSE_MANIFEST_FILENAME {
+                return true;
+            }
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            is_dir
+                && !file_name.to_string_lossy().starts_with('.')
+                && Self::contains_course_manifest(&entry.path())
+        })
+    }
+
+    /// Checks that the given path looks like a course library before Trane tries to open it, so
+    /// that common mistakes are reported with a clear message. A library is a directory that
+    /// already has a Trane config directory or contains at least one course. Empty directories are
+    /// accepted so that new libraries can be created and populated with repositories.
+    fn check_library_root(library_root: &Path) -> Result<()> {
+        ensure!(
+            library_root.exists(),
+            "library path {} not found",
+            library_root.display()
+        );
+        ensure!(
+            library_root.is_dir(),
+            "library path {} is not a directory",
+            library_root.display()
+        );
+        if library_root.join(TRANE_CONFIG_DIR_PATH).is_dir() {
+            return Ok(());
+        }
+
+        let is_empty = read_dir(library_root)
+            .with_context(|| format!("failed to read library path {}", library_root.display()))?
+            .next()
+            .is_none();
+        ensure!(
+            is_empty || Self::contains_course_manifest(library_root),
+            "path {} exists but doesn't look like a Trane library: it has no \
+            {TRANE_CONFIG_DIR_PATH} directory and no {COURSE_MANIFEST_FILENAME} files",
+            library_root.display()
+        );
+        Ok(())
+    }
+
+    /// Opens the course library at the given path. The active filter of the library that was open
+    /// before, if any, is saved first.
+    pub fn open_library(&mut self, library_root: &str) -> Result<()> {
+        Self::check_library_root(Path::new(library_root))?;
+        let mut trane = Trane::new_local(&std::env::current_dir()?, Path::new(library_root))
+            .with_context(|| format!("failed to open the course library at {library_root}"))?;
+        if let Some(batch_size) = self.default_batch_size {
+            let library_batch_size = trane
+                .get_user_preferences()?
+                .scheduler
+                .and_then(|preferences| preferences.batch_size);
+            if library_batch_size.is_none() {
+                let mut options = trane.get_scheduler_options();
+                options.batch_size = batch_size;
+                trane.set_scheduler_options(options);
+            }
+        }
+        self.save_active_filter()?;
+        self.save_mantra_count()?;
+        let config_dir = Path::new(library_root).join(TRANE_CONFIG_DIR_PATH);
+        self.notes = Some(ExerciseNotes::new_from_disk(&config_dir)?);
+        self.trial_notes = Some(TrialNotes::new_from_disk(&config_dir)?);
+        self.bookmarks = Some(Bookmarks::new_from_disk(&config_dir)?);
+        self.repository_updates = Some(RepositoryUpdates::new_from_disk(&config_dir)?);
+        self.repository_refs = Some(RepositoryRefs::new_from_disk(&config_dir)?);
+        self.suspensions = Some(Suspensions::new_from_disk(
+            &config_dir,
+            Utc::now().timestamp(),
+        )?);
+        *self.unit_ids.borrow_mut() = Self::collect_unit_ids(&trane);
+        self.trane = Some(trane);
+        self.saved_mantra_count = 0;
+        self.batch.drain(..);
+        self.batch_index = 0;
+        self.recent_exercises.clear();
+        self.last_submitted_score = None;
+        self.restore_active_filter(&config_dir);
+        Ok(())
+    }
+
+    /// Assigns the given score to the current exercise, along with an optional note that is saved
+    /// with the trial when the score is submitted.
+    pub fn record_score(&mut self, score: u8, note: Option<String>) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        let mastery_score = match score {
+            1 => Ok(MasteryScore::One),
+            2 => Ok(MasteryScore::Two),
+            3 => Ok(MasteryScore::Three),
+            4 => Ok(MasteryScore::Four),
+            5 => Ok(MasteryScore::Five),
+            _ => Err(anyhow!("invalid score {}", score)),
+        }?;
+        let note = note.map(|note| note.trim().to_string());
+        ensure!(
+            note.as_ref().is_none_or(|note| !note.is_empty()),
+            "the note cannot be empty"
+        );
+        self.current_score = Some(mastery_score);
+        self.current_score_note = note;
+        Ok(())
+    }
+
+    /// Sets the unit filter to the saved filter with the given ID. Setting a filter resets the
+    /// study session, as only one of the two can be active at a time.
+    pub fn set_filter(&mut self, filter_id: &str) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let saved_filter = self
+            .trane
+            .as_ref()
+            .unwrap()
+            .get_filter(filter_id)
+            .ok_or_else(|| anyhow!("no filter with ID {}", filter_id))?;
+        self.replace_filter(Some(saved_filter.filter));
+        self.study_session = None;
+        self.reset_batch();
+        Ok(())
+    }
+
+    /// Shows the answer to the current exercise.
+    pub fn show_answer(&mut self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let curr_exercise = self.oriented_exercise(&self.current_exercise()?);
+        if self.focus_mode {
+            curr_exercise.exercise_asset.display_answer()?;
+        } else {
+            curr_exercise.display_answer()?;
+        }
+
+        // Point to the local copy of the recording of a transcription exercise, if it was
+        // downloaded.
+        let trane = self.trane.as_ref().unwrap();
+        if matches!(
+            curr_exercise.exercise_asset,
+            ExerciseAsset::TranscriptionAsset {
+                external_link: Some(_),
+                ..
+            }
+        ) && trane.is_transcription_asset_downloaded(curr_exercise.id)
+        {
+            let path = trane
+                .transcription_download_path_alias(curr_exercise.id)
+                .or_else(|| trane.transcription_download_path(curr_exercise.id));
+            if let Some(path) = path {
+                println!("Downloaded recording: {}", path.display());
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the number of seconds the `answer` command waits before showing the answer.
+    pub fn set_reveal_delay(&mut self, seconds: u64) {
+        self.reveal_delay = seconds;
+    }
+
+    /// Returns the number of seconds the `answer` command waits before showing the answer.
+    pub fn reveal_delay(&self) -> u64 {
+        self.reveal_delay
+    }
+
+    /// Shows the answer to the current exercise after counting down the given number of seconds,
+    /// or the configured reveal delay if none is given. The countdown can be cancelled, in which
+    /// case the answer is not shown.
+    pub fn reveal_answer(&mut self, delay: Option<u64>) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        // Fail before the countdown if there's no exercise to show the answer for.
+        self.current_exercise()?;
+        let delay = delay.unwrap_or(self.reveal_delay);
+        if delay > 0 && !countdown(delay)? {
+            println!("Cancelled showing the answer");
+            return Ok(());
+        }
+        self.show_answer()
+    }
+
+    /// Opens the link to the `SoundSlice` asset of the current exercise in the default browser.
+    pub fn open_asset(&self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let curr_exercise = self.current_exercise()?;
+        if let ExerciseAsset::SoundSliceAsset { link, .. } = &curr_exercise.exercise_asset {
+            open::that(link).with_context(|| format!("failed to open {link} in the browser"))?;
+            println!("Opened {link} in the browser");
+        } else {
+            println!(
+                "The current exercise {} does not have a SoundSlice asset",
+                curr_exercise.id
+            );
+        }
+        Ok(())
+    }
+
+    /// Enables or disables focus mode.
+    pub fn set_focus_mode(&mut self, enabled: bool) {
+        self.focus_mode = enabled;
+    }
+
+    /// Enables or disables showing flashcards back to front.
+    pub fn set_flip(&mut self, enabled: bool) {
+        self.flip = enabled;
+    }
+
+    /// Returns the exercise as it should be shown, which is with the sides of its flashcard swapped
+    /// if flip is on. Exercises that are not flashcards or that have no back are returned as is.
+    fn oriented_exercise(&self, manifest: &ExerciseManifest) -> ExerciseManifest {
+        if self.flip {
+            if let Some(flipped) = flip_flashcard(manifest) {
+                return flipped;
+            }
+        }
+        manifest.clone()
+    }
+
+    /// Enables or disables showing the course ID of the current exercise in the prompt.
+    pub fn set_course_in_prompt(&mut self, enabled: bool) {
+        self.course_in_prompt = enabled;
+    }
+
+    /// Returns the prompt shown before reading each command. It includes the course ID of the
+    /// current exercise if enabled, unless focus mode is on. Long IDs are truncated at the start,
+    /// since the end of the ID is usually the most specific part.
+    pub fn prompt(&self) -> String {
+        let course_id = match self.current_exercise() {
+            Ok(manifest) if self.course_in_prompt && !self.focus_mode => manifest.course_id,
+            _ => return DEFAULT_PROMPT.to_string(),
+        };
+        let num_chars = course_id.chars().count();
+        if num_chars <= MAX_PROMPT_ID_LENGTH {
+            format!("trane [{course_id}] >> ")
+        } else {
+            let tail: String = course_id
+                .chars()
+                .skip(num_chars - MAX_PROMPT_ID_LENGTH + 1)
+                .collect();
+            format!("trane […{tail}] >> ")
+        }
+    }
+
+    /// Suspends the given unit for the given number of days, so that its exercises are not shown
+    /// until then. Returns the time at which the suspension expires.
+    pub fn suspend_unit(&mut self, unit_id: Ustr, days: u32) -> Result<i64> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+        ensure!(days > 0, "the number of days must be at least one");
+
+        self.get_unit_type(unit_id)?;
+        let expiration = Utc::now().timestamp() + i64::from(days) * SECONDS_PER_DAY;
+        self.suspensions
+            .as_mut()
+            .unwrap()
+            .suspend(unit_id, expiration)?;
+        self.reset_batch();
+        Ok(expiration)
+    }
+
+    /// Lifts the suspension of the given unit before it expires.
+    pub fn resume_unit(&mut self, unit_id: Ustr) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        self.suspensions.as_mut().unwrap().resume(unit_id)?;
+        self.reset_batch();
+        Ok(())
+    }
+
+    /// Lists the units that are currently suspended and when their suspensions expire.
+    pub fn list_suspensions(&self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let now = Utc::now().timestamp();
+        let suspensions = self.suspensions.as_ref().unwrap().get_suspensions(now);
+        if self.output_format == OutputFormat::Json {
+            let suspensions: Vec<_> = suspensions
+                .iter()
+                .map(|(unit_id, expiration)| {
+                    json!({
+                        "unit_id": unit_id,
+                        "expiration": expiration,
+                    })
+                })
+                .collect();
+            return print_json(&json!(suspensions));
+        }
+        if suspensions.is_empty() {
+            println!("No units are suspended");
+            return Ok(());
+        }
+
+        let mut pager = Pager::new();
+        let id_width = column_width(
+            "Unit ID",
+            suspensions.iter().map(|(unit_id, _)| unit_id.as_str()),
+            42,
+            50,
+        );
+        pager.println(&format!(
+            "{:<id_width$} {:<20} Remaining",
+            "Unit ID", "Expires"
+        ))?;
+        for (unit_id, expiration) in suspensions {
+            let expires = Local
+                .timestamp_opt(expiration, 0)
+                .earliest()
+                .map_or_else(String::new, |dt| dt.format("%Y-%m-%d %H:%M").to_string());
+            if !pager.println(&format!(
+                "{:<id_width$} {expires:<20} {}",
+                unit_id.as_str(),
+                remaining_time(expiration, now)
+            ))? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists all the entries in the blacklist.
+    pub fn list_blacklist(&self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let trane = self.trane.as_ref().unwrap();
+        let entries = trane.get_blacklist_entries()?;
+        if self.output_format == OutputFormat::Json {
+            let entries: Vec<_> = entries
+                .iter()
+                .map(|unit_id| {
+                    json!({
+                        "unit_type": trane.get_unit_type(*unit_id).map(|ut| ut.to_string()),
+                        "unit_id": unit_id,
+                    })
+                })
+                .collect();
+            return print_json(&json!(entries));
+        }
+        if entries.is_empty() {
+            println!("No entries in the blacklist");
+            return Ok(());
+        }
+
+        let mut pager = Pager::new();
+        pager.println(&format!("{:<15} Unit ID", "Unit Type"))?;
+        for unit_id in entries {
+            let unit_type = if let Some(ut) = trane.get_unit_type(unit_id) {
+                ut.to_string()
+            } else {
+                "Unknown".to_string()
+            };
+            if !pager.println(&format!("{unit_type:<15} {unit_id}"))? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Shows the currently set filter.
+    pub fn show_filter(&self) {
+        if self.filter.is_none() {
+            println!("No filter is set");
+        } else {
+            println!("Filter:");
+            println!("{:#?}", self.filter.as_ref().unwrap());
+        }
+    }
+
+    /// Shows the course instructions for the given course.
+    pub fn show_course_instructions(&self, course_id: Ustr) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let course_id = self.course_id_or_current(course_id)?;
+        let manifest = self
+            .trane
+            .as_ref()
+            .unwrap()
+            .get_course_manifest(course_id)
+            .ok_or_else(|| anyhow!("no manifest for course with ID {}", course_id))?;
+        match manifest.course_instructions {
             None => {
                 println!("Course has no instructions");
                 Ok(())
@@ -817,6 +2658,38 @@ impl TraneApp {
         }
     }
 
+    /// Shows the instructions of the current exercise's lesson, falling back to the instructions of
+    /// its course if the lesson has none.
+    pub fn show_hint(&self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let lesson_id = self.lesson_id_or_current(Ustr::default())?;
+        let lesson_manifest = self
+            .trane
+            .as_ref()
+            .unwrap()
+            .get_lesson_manifest(lesson_id)
+            .ok_or_else(|| anyhow!("no manifest for lesson with ID {lesson_id}"))?;
+        if let Some(instructions) = lesson_manifest.lesson_instructions {
+            return instructions.display_asset();
+        }
+
+        let course_id = self.course_id_or_current(Ustr::default())?;
+        let course_manifest = self
+            .trane
+            .as_ref()
+            .unwrap()
+            .get_course_manifest(course_id)
+            .ok_or_else(|| anyhow!("no manifest for course with ID {course_id}"))?;
+        match course_manifest.course_instructions {
+            None => {
+                println!("Neither the lesson nor the course have instructions");
+                Ok(())
+            }
+            Some(instructions) => instructions.display_asset(),
+        }
+    }
+
     /// Shows the course material for the given course.
     pub fn show_course_material(&self, course_id: Ustr) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
@@ -861,17 +2734,44 @@ impl TraneApp {
     /// `mantra-mining` library in the background as a symbolic way in which users can contribute
     /// back to the maintainers of this program. See more information in the README of the
     /// `mantra-mining` library.
-    pub fn show_mantra_count(&self) -> Result<()> {
+    ///
+    /// If `lifetime` is true, the count also includes the mantras recited in all the previous
+    /// sessions with the open library.
+    pub fn show_mantra_count(&self, lifetime: bool) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
-        println!(
-            "Mantra count: {}",
-            self.trane.as_ref().unwrap().mantra_count()
-        );
-        Ok(())
-    }
-
+        let trane = self.trane.as_ref().unwrap();
+        if lifetime {
+            let config_dir = Path::new(&trane.library_root()).join(TRANE_CONFIG_DIR_PATH);
+            let previous_count = load_lifetime_count(&config_dir)?;
+            println!(
+                "Lifetime mantra count: {}",
+                previous_count + trane.mantra_count() - self.saved_mantra_count
+            );
+        } else {
+            println!("Mantra count: {}", trane.mantra_count());
+        }
+        Ok(())
+    }
+
+    /// Adds the mantras recited by the open Trane instance since the last time the count was saved
+    /// to the lifetime count stored in the library. It must be called before the instance is closed
+    /// or replaced. Nothing is saved in read-only mode.
+    pub fn save_mantra_count(&mut self) -> Result<()> {
+        let Some(trane) = self.trane.as_ref() else {
+            return Ok(());
+        };
+        if self.read_only {
+            return Ok(());
+        }
+        let config_dir = Path::new(&trane.library_root()).join(TRANE_CONFIG_DIR_PATH);
+        let mantra_count = trane.mantra_count();
+        add_to_lifetime_count(&config_dir, mantra_count - self.saved_mantra_count)?;
+        self.saved_mantra_count = mantra_count;
+        Ok(())
+    }
+
     /// Shows the most recent scores for the given exercise.
-    pub fn show_scores(&self, exercise_id: Ustr, num_scores: usize) -> Result<()> {
+    pub fn show_scores(&self, exercise_id: Ustr, num_scores: usize, graph: bool) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
         // Retrieve and validate the exercise ID.
@@ -891,22 +2791,91 @@ impl TraneApp {
         let aggregate_score = simple_scorer.score(&scores)?;
 
         // Print the scores.
+        if self.output_format == OutputFormat::Json {
+            let trials: Vec<_> = scores
+                .iter()
+                .map(|trial| {
+                    json!({
+                        "timestamp": trial.timestamp,
+                        "score": trial.score,
+                        "note": self.trial_note(exercise_id, trial.timestamp),
+                    })
+                })
+                .collect();
+            return print_json(&json!({
+                "exercise_id": exercise_id,
+                "aggregate_score": aggregate_score,
+                "scores": trials,
+            }));
+        }
         println!("Scores for exercise {exercise_id}:");
-        println!("Aggregate score: {aggregate_score:.2}");
+        println!(
+            "Aggregate score: {}",
+            color_by_score(&format!("{aggregate_score:.2}"), aggregate_score, true)
+        );
         println!();
-        println!("{:<25} {:>6}", "Date", "Score");
+        if graph {
+            Self::print_scores_graph(&scores);
+            return Ok(());
+        }
+        // Only show the column with the notes if at least one of the trials has one.
+        let has_notes = scores
+            .iter()
+            .any(|trial| self.trial_note(exercise_id, trial.timestamp).is_some());
+        if has_notes {
+            println!("{:<25} {:>6}  {:<16} Note", "Date", "Score", "When");
+        } else {
+            println!("{:<25} {:>6}  When", "Date", "Score");
+        }
+        let now = Utc::now().timestamp();
         for score in scores {
             if let Some(dt) = Local.timestamp_opt(score.timestamp, 0).earliest() {
-                println!(
-                    "{:<25} {:>6}",
-                    dt.format("%Y-%m-%d %H:%M:%S"),
-                    score.score as u8
-                );
+                let score_text =
+                    color_by_score(&format!("{:>6}", score.score as u8), score.score, false);
+                let when = relative_time(score.timestamp, now);
+                let date = dt.format("%Y-%m-%d %H:%M:%S").to_string();
+                if has_notes {
+                    let note = self.trial_note(exercise_id, score.timestamp).unwrap_or("");
+                    let line = format!("{date:<25} {score_text}  {when:<16} {note}");
+                    println!("{}", line.trim_end());
+                } else {
+                    println!("{date:<25} {score_text}  {when}");
+                }
             }
         }
         Ok(())
     }
 
+    /// Returns the note attached to the trial of the given exercise at the given timestamp.
+    fn trial_note(&self, exercise_id: Ustr, timestamp: i64) -> Option<&str> {
+        self.trial_notes
+            .as_ref()
+            .and_then(|trial_notes| trial_notes.get_note(exercise_id, timestamp))
+    }
+
+    /// Prints a sparkline of the given scores from oldest to newest. Only the most recent scores
+    /// that fit in the width of the terminal are shown.
+    fn print_scores_graph(scores: &[ExerciseTrial]) {
+        if scores.is_empty() {
+            println!("No scores recorded for this exercise");
+            return;
+        }
+
+        // The scores are retrieved from newest to oldest.
+        let width = terminal_width().max(1);
+        let values: Vec<f32> = scores
+            .iter()
+            .take(width)
+            .rev()
+            .map(|trial| trial.score)
+            .collect();
+        println!("Scores from oldest to newest (1 = ▁, 5 = █):");
+        println!("{}", sparkline(&values, 1.0, 5.0));
+        if values.len() == 1 {
+            println!("Only one score has been recorded, so there's no trend to show yet");
+        }
+    }
+
     /// Prints the manifest for the unit with the given UID.
     fn show_unit_manifest(&self, unit_id: Ustr, unit_type: &UnitType) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
@@ -946,20 +2915,530 @@ impl TraneApp {
         Ok(())
     }
 
-    /// Prints information about the given unit.
-    pub fn show_unit_info(&self, unit_id: Ustr) -> Result<()> {
+    /// Escapes backslashes, tabs, and newlines in a value printed in porcelain mode, so that every
+    /// field fits in a single line and the tab separator is unambiguous.
+    fn porcelain_escape(value: &str) -> String {
+        value
+            .replace('\\', "\\\\")
+            .replace('\t', "\\t")
+            .replace('\n', "\\n")
+    }
+
+    /// Returns the lines of the given unit's manifest printed in porcelain mode, in a fixed order.
+    /// Each line is a field name followed by its values. Optional fields that are not set are
+    /// omitted, and list fields produce one line per element.
+    fn porcelain_lines(
+        &self,
+        unit_id: Ustr,
+        unit_type: &UnitType,
+    ) -> Result<Vec<(&str, Vec<String>)>> {
+        let trane = self.trane.as_ref().unwrap();
+        let mut fields = vec![("id", unit_id.to_string()), ("type", unit_type.to_string())];
+        let (dependencies, superseded, authors, metadata) = match unit_type {
+            UnitType::Course => {
+                let manifest = trane
+                    .get_course_manifest(unit_id)
+                    .ok_or_else(|| anyhow!("missing manifest for course {unit_id}"))?;
+                fields.push(("name", manifest.name));
+                fields.extend(manifest.description.map(|d| ("description", d)));
+                (
+                    manifest.dependencies,
+                    manifest.superseded,
+                    manifest.authors.unwrap_or_default(),
+                    manifest.metadata,
+                )
+            }
+            UnitType::Lesson => {
+                let manifest = trane
+                    .get_lesson_manifest(unit_id)
+                    .ok_or_else(|| anyhow!("missing manifest for lesson {unit_id}"))?;
+                fields.push(("course_id", manifest.course_id.to_string()));
+                fields.push(("name", manifest.name));
+                fields.extend(manifest.description.map(|d| ("description", d)));
+                (
+                    manifest.dependencies,
+                    manifest.superseded,
+                    vec![],
+                    manifest.metadata,
+                )
+            }
+            UnitType::Exercise => {
+                let manifest = trane
+                    .get_exercise_manifest(unit_id)
+                    .ok_or_else(|| anyhow!("missing manifest for exercise {unit_id}"))?;
+                fields.push(("course_id", manifest.course_id.to_string()));
+                fields.push(("lesson_id", manifest.lesson_id.to_string()));
+                fields.push(("name", manifest.name));
+                fields.extend(manifest.description.map(|d| ("description", d)));
+                let exercise_type = match manifest.exercise_type {
+                    ExerciseType::Declarative => "Declarative",
+                    ExerciseType::Procedural => "Procedural",
+                };
+                fields.push(("exercise_type", exercise_type.to_string()));
+                let asset_type = match manifest.exercise_asset {
+                    ExerciseAsset::BasicAsset(_) => "BasicAsset",
+                    ExerciseAsset::FlashcardAsset { .. } => "FlashcardAsset",
+                    ExerciseAsset::LiteracyAsset { .. } => "LiteracyAsset",
+                    ExerciseAsset::SoundSliceAsset { .. } => "SoundSliceAsset",
+                    ExerciseAsset::TranscriptionAsset { .. } => "TranscriptionAsset",
+                };
+                fields.push(("asset_type", asset_type.to_string()));
+                (vec![], vec![], vec![], None)
+            }
+        };
+
+        fields.extend(dependencies.iter().map(|id| ("dependency", id.to_string())));
+        fields.extend(superseded.iter().map(|id| ("superseded", id.to_string())));
+        fields.extend(authors.into_iter().map(|author| ("author", author)));
+        let mut lines: Vec<(&str, Vec<String>)> = fields
+            .into_iter()
+            .map(|(key, value)| (key, vec![value]))
+            .collect();
+        for (key, values) in metadata.unwrap_or_default() {
+            for value in values {
+                lines.push(("metadata", vec![key.clone(), value]));
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Prints information about the given unit. If the ID is empty or `?`, the user picks the unit
+    /// interactively. In porcelain mode, the fields of the manifest are printed in the stable
+    /// format described in the help of the `debug unit-info` command instead of the debug format.
+    pub fn show_unit_info(&self, unit_id: Ustr, porcelain: bool) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
+        let unit_id = self.unit_id_or_pick(unit_id, None)?;
         let unit_type = self.get_unit_type(unit_id)?;
+        if porcelain {
+            for (key, values) in self.porcelain_lines(unit_id, &unit_type)? {
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|value| Self::porcelain_escape(value))
+                    .collect();
+                println!("{key}\t{}", values.join("\t"));
+            }
+            return Ok(());
+        }
         println!("Unit ID: {unit_id}");
         println!("Unit Type: {unit_type}");
         self.show_unit_manifest(unit_id, &unit_type)
     }
 
+    /// Returns whether the given dependency is considered satisfied by the scheduler, along with
+    /// the reason. Blacklisted dependencies, including lessons in a blacklisted course, are always
+    /// satisfied. Other dependencies are satisfied if their average score reaches the passing score.
+    fn dependency_status(&self, dependency_id: Ustr, passing_score: f32) -> Result<String> {
+        let trane = self.trane.as_ref().unwrap();
+        if trane.get_unit_type(dependency_id).is_none() {
+            return Ok("not found in the library".to_string());
+        }
+        let course_id = trane.get_lesson_course(dependency_id);
+        for unit_id in [Some(dependency_id), course_id].into_iter().flatten() {
+            if trane.blacklisted(unit_id)? {
+                return Ok("satisfied (blacklisted)".to_string());
+            }
+        }
+        Ok(match self.unit_mastery(dependency_id)? {
+            Some(score) if score >= passing_score => format!("satisfied (score {score:.2})"),
+            Some(score) => format!("not satisfied (score {score:.2})"),
+            None => "not satisfied (no scores)".to_string(),
+        })
+    }
+
+    /// Prints a report explaining why the scheduler might show the given exercise: its lesson and
+    /// course, its aggregate score, whether it or its ancestors are in the blacklist or the review
+    /// list, and whether the dependencies of its lesson and course are satisfied. The passing score
+    /// at the starting depth is used, and superseded units are not taken into account.
+    pub fn explain_exercise(&self, exercise_id: Ustr) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let exercise_id = self.exercise_id_or_current(exercise_id)?;
+        let manifest = self.get_exercise_manifest(exercise_id)?;
+        let trane = self.trane.as_ref().unwrap();
+        let options = trane.get_scheduler_options();
+        let passing_score = options.passing_score.compute_score(0);
+        println!("Exercise ID: {exercise_id}");
+        println!("Lesson ID: {}", manifest.lesson_id);
+        println!("Course ID: {}", manifest.course_id);
+
+        let trials = trane.get_scores(exercise_id, options.num_trials)?;
+        if trials.is_empty() {
+            println!("Aggregate score: no scores");
+        } else {
+            println!(
+                "Aggregate score: {:.2} ({} trials)",
+                SimpleScorer {}.score(&trials)?,
+                trials.len()
+            );
+        }
+        println!("Passing score: {passing_score:.2}");
+
+        // Show which of the exercise, its lesson, or its course is in each list.
+        let units = [
+            ("exercise", exercise_id),
+            ("lesson", manifest.lesson_id),
+            ("course", manifest.course_id),
+        ];
+        let review_list = trane.get_review_list_entries()?;
+        let mut blacklisted = Vec::new();
+        let mut in_review_list = Vec::new();
+        for (unit_type, unit_id) in units {
+            if trane.blacklisted(unit_id)? {
+                blacklisted.push(unit_type);
+            }
+            if review_list.contains(&unit_id) {
+                in_review_list.push(unit_type);
+            }
+        }
+        let describe = |unit_types: &[&str]| {
+            if unit_types.is_empty() {
+                "no".to_string()
+            } else {
+                format!("yes ({})", unit_types.join(", "))
+            }
+        };
+        println!("Blacklisted: {}", describe(&blacklisted));
+        println!("In review list: {}", describe(&in_review_list));
+
+        // Show the state of the dependencies of the lesson and the course.
+        for (unit_type, unit_id) in &units[1..] {
+            let mut dependencies: Vec<Ustr> = trane
+                .get_dependencies(*unit_id)
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+            dependencies.sort();
+            println!();
+            if dependencies.is_empty() {
+                println!("The {unit_type} has no dependencies");
+                continue;
+            }
+            println!("Dependencies of the {unit_type}:");
+            for dependency_id in dependencies {
+                println!(
+                    "  {:<50} {}",
+                    dependency_id.as_str(),
+                    self.dependency_status(dependency_id, passing_score)?
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the path to the practice stats database of the open library.
+    fn stats_db_path(&self) -> Result<PathBuf> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        Ok(Path::new(&self.trane.as_ref().unwrap().library_root())
+            .join(TRANE_CONFIG_DIR_PATH)
+            .join(PRACTICE_STATS_PATH))
+    }
+
+    /// Opens the practice stats database in read-only mode. Returns the path to the database along
+    /// with the connection.
+    fn open_stats_db(&self) -> Result<(PathBuf, Connection)> {
+        let db_path = self.stats_db_path()?;
+        let connection = Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        Ok((db_path, connection))
+    }
+
+    /// Returns the number of trials recorded today and the number of consecutive days, up to today
+    /// or yesterday, on which at least one trial was recorded.
+    fn practice_activity(&self) -> Result<(usize, usize)> {
+        let (_, connection) = self.open_stats_db()?;
+        let mut stmt = connection.prepare("SELECT timestamp FROM practice_stats;")?;
+        let mut num_today = 0;
+        let mut practice_days = BTreeSet::new();
+        let today = Local::now().date_naive();
+        for timestamp in stmt.query_map([], |row| row.get::<_, i64>(0))? {
+            if let Some(dt) = Local.timestamp_opt(timestamp?, 0).earliest() {
+                if dt.date_naive() == today {
+                    num_today += 1;
+                }
+                practice_days.insert(dt.date_naive());
+            }
+        }
+
+        // The streak is not broken until a full day passes without practice.
+        let mut day = if practice_days.contains(&today) {
+            today
+        } else {
+            today - Duration::days(1)
+        };
+        let mut streak = 0;
+        while practice_days.contains(&day) {
+            streak += 1;
+            day -= Duration::days(1);
+        }
+        Ok((num_today, streak))
+    }
+
+    /// Returns a short description of the given unit filter.
+    fn describe_filter(filter: &UnitFilter) -> String {
+        let join_ids = |ids: &[Ustr]| ids.iter().map(Ustr::as_str).collect::<Vec<_>>().join(", ");
+        match filter {
+            UnitFilter::CourseFilter { course_ids } => format!("courses {}", join_ids(course_ids)),
+            UnitFilter::LessonFilter { lesson_ids } => format!("lessons {}", join_ids(lesson_ids)),
+            UnitFilter::MetadataFilter { .. } => "metadata filter".to_string(),
+            UnitFilter::ReviewListFilter => "review list".to_string(),
+            UnitFilter::Dependents { unit_ids } => {
+                format!("dependents of {}", join_ids(unit_ids))
+            }
+            UnitFilter::Dependencies { unit_ids, depth } => {
+                format!("dependencies of {} (depth {depth})", join_ids(unit_ids))
+            }
+        }
+    }
+
+    /// Returns a line describing the active filter or study session.
+    fn describe_active_filter(&self) -> String {
+        match (&self.filter, &self.study_session) {
+            (Some(_), _) if self.filter_exercise_ids.is_some() => format!(
+                "Filter: exercises {}",
+                self.filter_exercise_ids
+                    .as_ref()
+                    .unwrap()
+                    .iter()
+                    .map(Ustr::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            (Some(filter), _) if self.linear_exercise_ids.is_some() => {
+                format!("Filter: {} in order", Self::describe_filter(filter))
+            }
+            (Some(filter), _) => format!("Filter: {}", Self::describe_filter(filter)),
+            (None, Some(session)) => format!("Study session: {}", session.definition.id),
+            (None, None) => "Filter: none".to_string(),
+        }
+    }
+
+    /// Prints a summary of the current state of the application. Lines that require an open
+    /// library show a placeholder if none is open.
+    pub fn show_status(&self) -> Result<()> {
+        let Some(trane) = self.trane.as_ref() else {
+            for label in [
+                "Library",
+                "Filter",
+                "Batch",
+                "Batch size",
+                "Blacklist entries",
+                "Review list entries",
+                "Practice streak",
+                "Exercises practiced today",
+            ] {
+                println!("{label}: no library open");
+            }
+            return Ok(());
+        };
+
+        println!("Library: {}", trane.library_root());
+        println!("{}", self.describe_active_filter());
+        if self.batch.is_empty() {
+            println!("Batch: empty");
+        } else {
+            println!(
+                "Batch: exercise {} of {}",
+                self.batch_index + 1,
+                self.batch.len()
+            );
+        }
+        println!("Batch size: {}", trane.get_scheduler_options().batch_size);
+        println!(
+            "Blacklist entries: {}",
+            trane.get_blacklist_entries()?.len()
+        );
+        println!(
+            "Review list entries: {}",
+            trane.get_review_list_entries()?.len()
+        );
+        let (num_today, streak) = self.practice_activity()?;
+        println!("Practice streak: {streak} days");
+        println!("Exercises practiced today: {num_today}");
+        Ok(())
+    }
+
+    /// Returns a short description of the given mastery window.
+    fn describe_window(window: &MasteryWindow) -> String {
+        format!(
+            "{:.0}% of the batch, scores from {:.2} to {:.2}",
+            window.percentage * 100.0,
+            window.range.0,
+            window.range.1
+        )
+    }
+
+    /// Prints the settings and paths in effect, such as the open library, the location of the
+    /// config and history files, the transcription download path, and the scheduler options.
+    /// Settings that require an open library show a placeholder if none is open.
+    pub fn show_config(&self) -> Result<()> {
+        let history_file = self
+            .history_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(crate::HISTORY_FILE));
+        let history_path = std::env::current_dir()
+            .map_or_else(|_| history_file.clone(), |dir| dir.join(&history_file));
+        let config_file = config_path().filter(|path| path.exists()).map_or_else(
+            || "not found".to_string(),
+            |path| path.display().to_string(),
+        );
+        let Some(trane) = self.trane.as_ref() else {
+            println!("Library: no library open");
+            println!("Config file: {config_file}");
+            println!("History file: {}", history_path.display());
+            return Ok(());
+        };
+
+        println!("Library: {}", trane.library_root());
+        println!("{}", self.describe_active_filter());
+        println!("Config file: {config_file}");
+        println!("History file: {}", history_path.display());
+        let transcription = trane
+            .get_user_preferences()?
+            .transcription
+            .unwrap_or_default();
+        println!(
+            "Transcription download path: {}",
+            transcription.download_path.as_deref().unwrap_or("not set")
+        );
+        println!(
+            "Transcription download path alias: {}",
+            transcription
+                .download_path_alias
+                .as_deref()
+                .unwrap_or("not set")
+        );
+
+        let options = trane.get_scheduler_options();
+        println!("Scheduler options:");
+        println!("  Batch size: {}", options.batch_size);
+        for (name, window) in [
+            ("New", &options.new_window_opts),
+            ("Target", &options.target_window_opts),
+            ("Current", &options.current_window_opts),
+            ("Easy", &options.easy_window_opts),
+            ("Mastered", &options.mastered_window_opts),
+        ] {
+            println!("  {name} window: {}", Self::describe_window(window));
+        }
+        match options.passing_score {
+            PassingScoreOptions::ConstantScore(score) => {
+                println!("  Passing score: {score:.2}");
+            }
+            PassingScoreOptions::IncreasingScore {
+                starting_score,
+                step_size,
+                max_steps,
+            } => println!(
+                "  Passing score: {starting_score:.2}, increasing by {step_size:.2} per level of \
+                depth for up to {max_steps} levels"
+            ),
+        }
+        println!("  Superseding score: {:.2}", options.superseding_score);
+        println!("  Trials used to compute scores: {}", options.num_trials);
+        Ok(())
+    }
+
+    /// Opens the practice stats database in read-only mode and prints a report on the stored
+    /// trials, including any trials for exercises that no longer exist in the library.
+    pub fn verify_stats(&self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let trane = self.trane.as_ref().unwrap();
+        let (db_path, connection) = self.open_stats_db()?;
+
+        // Gather the totals and the range of the timestamps.
+        let (num_trials, earliest, latest): (i64, Option<i64>, Option<i64>) = connection
+            .query_row(
+                "SELECT COUNT(*), MIN(timestamp), MAX(timestamp) FROM practice_stats;",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+        let num_dangling: i64 = connection.query_row(
+            "SELECT COUNT(*) FROM practice_stats
+                WHERE unit_uid NOT IN (SELECT unit_uid FROM uids);",
+            [],
+            |row| row.get(0),
+        )?;
+
+        // Find the exercises with trials that are not part of the library.
+        let mut stmt = connection.prepare(
+            "SELECT uids.unit_id, COUNT(*) FROM practice_stats
+                JOIN uids ON practice_stats.unit_uid = uids.unit_uid
+                GROUP BY uids.unit_id ORDER BY uids.unit_id;",
+        )?;
+        let exercises = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let orphaned: Vec<_> = exercises
+            .iter()
+            .filter(|(exercise_id, _)| {
+                trane.get_unit_type(Ustr::from(exercise_id)) != Some(UnitType::Exercise)
+            })
+            .collect();
+
+        // Print the report.
+        let format_timestamp = |timestamp: Option<i64>| {
+            timestamp
+                .and_then(|timestamp| Local.timestamp_opt(timestamp, 0).earliest())
+                .map_or_else(
+                    || "N/A".to_string(),
+                    |dt| dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+                )
+        };
+        println!("Practice stats database: {}", db_path.display());
+        println!("Total trials: {num_trials}");
+        println!("Exercises with trials: {}", exercises.len());
+        println!("Earliest trial: {}", format_timestamp(earliest));
+        println!("Latest trial: {}", format_timestamp(latest));
+        println!("Trials without a matching exercise ID: {num_dangling}");
+        if orphaned.is_empty() {
+            println!("All exercises with trials exist in the library");
+        } else {
+            println!();
+            println!("Exercises with trials which are not in the library:");
+            println!("{:<8} Exercise ID", "Trials");
+            for (exercise_id, count) in orphaned {
+                println!("{count:<8} {exercise_id}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints the total number of trials ever recorded, the number of distinct exercises practiced,
+    /// and the date of the first trial.
+    pub fn show_lifetime(&self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let (_, connection) = self.open_stats_db()?;
+        let (num_trials, num_exercises, earliest): (i64, i64, Option<i64>) = connection.query_row(
+            "SELECT COUNT(*), COUNT(DISTINCT unit_uid), MIN(timestamp) FROM practice_stats;",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        println!("Total trials: {num_trials}");
+        println!("Exercises practiced: {num_exercises}");
+        match earliest.and_then(|timestamp| Local.timestamp_opt(timestamp, 0).earliest()) {
+            Some(first) => {
+                let num_days = (Local::now().date_naive() - first.date_naive()).num_days();
+                println!(
+                    "First practice: {} ({num_days} days ago)",
+                    first.format("%Y-%m-%d")
+                );
+            }
+            None => println!("First practice: N/A"),
+        }
+        Ok(())
+    }
+
     /// Trims the scores for each exercise by removing all the scores except for the `num_scores`
     /// most recent scores.
     pub fn trim_scores(&mut self, num_scores: usize) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
         self.trane.as_mut().unwrap().trim_scores(num_scores)?;
         println!("Trimmed scores for all exercises");
         Ok(())
@@ -968,6 +3447,7 @@ impl TraneApp {
     /// Removes the scores for exercises that match the given prefix.
     pub fn remove_prefix_from_scores(&mut self, prefix: &str) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
         self.trane
             .as_mut()
             .unwrap()
@@ -976,283 +3456,1463 @@ impl TraneApp {
         Ok(())
     }
 
+    /// Returns the paths of the files referenced by the exercise's asset that do not exist.
+    fn missing_asset_paths(manifest: &ExerciseManifest) -> Vec<String> {
+        let paths = match &manifest.exercise_asset {
+            ExerciseAsset::BasicAsset(BasicAsset::MarkdownAsset { path }) => vec![path],
+            ExerciseAsset::FlashcardAsset {
+                front_path,
+                back_path,
+            } => std::iter::once(front_path).chain(back_path).collect(),
+            _ => vec![],
+        };
+        paths
+            .into_iter()
+            .filter(|path| !Path::new(path).exists())
+            .cloned()
+            .collect()
+    }
+
+    /// Scans all the exercises in the library and adds the ones whose assets reference missing
+    /// files to the blacklist. The exercises can be restored with the usual blacklist commands.
+    pub fn blacklist_broken_exercises(&mut self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        let mut broken_exercises = Vec::new();
+        for exercise_id in self.trane.as_ref().unwrap().get_all_exercise_ids(None) {
+            let manifest = self.get_exercise_manifest(exercise_id)?;
+            let missing_paths = Self::missing_asset_paths(&manifest);
+            if missing_paths.is_empty() {
+                continue;
+            }
+            println!("Blacklisting exercise {exercise_id}. Missing files:");
+            for path in missing_paths {
+                println!("  {path}");
+            }
+            broken_exercises.push(exercise_id);
+        }
+
+        if broken_exercises.is_empty() {
+            println!("No exercises with missing assets found");
+            return Ok(());
+        }
+        self.blacklist_units(&broken_exercises)?;
+        println!(
+            "Added {} exercises to the blacklist",
+            broken_exercises.len()
+        );
+        Ok(())
+    }
+
+    /// Removes the given units from the blacklist. The batch is reset only once, after all the
+    /// units have been removed.
+    pub fn remove_from_blacklist(&mut self, unit_ids: &[Ustr]) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        let trane = self.trane.as_mut().unwrap();
+        let result = unit_ids
+            .iter()
+            .try_for_each(|unit_id| trane.remove_from_blacklist(*unit_id));
+        self.reset_batch();
+        Ok(result?)
+    }
+
     /// Removes the given unit from the blacklist.
-    pub fn remove_from_blacklist(&mut self, unit_id: Ustr) -> Result<()> {
+    pub fn remove_prefix_from_blacklist(&mut self, prefix: &str) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        self.trane
+            .as_mut()
+            .unwrap()
+            .remove_prefix_from_blacklist(prefix)?;
+        self.reset_batch();
+        Ok(())
+    }
+
+    /// Returns the directory where the given managed repository is cloned.
+    fn repo_dir(&self, repo_id: &str) -> PathBuf {
+        Path::new(&self.trane.as_ref().unwrap().library_root())
+            .join(MANAGED_COURSES_DIR)
+            .join(repo_id)
+    }
+
+    /// Checks out the ref to which the given repository is pinned, if any. Called after every
+    /// update, since Trane clones the default branch again.
+    fn checkout_pinned_ref(&self, repo_id: &str) -> Result<()> {
+        match self.repository_refs.as_ref().unwrap().get(repo_id) {
+            Some(git_ref) => checkout_ref(&self.repo_dir(repo_id), git_ref),
+            None => Ok(()),
+        }
+    }
+
+    /// Adds a new repository to the Trane instance. If a ref is given, the repository is pinned to
+    /// it. The repository is removed again if the ref cannot be checked out.
+    pub fn add_repo(
+        &mut self,
+        url: &str,
+        repo_id: Option<String>,
+        git_ref: Option<&str>,
+    ) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+        let trane = self.trane.as_mut().unwrap();
+        trane.add_repo(url, repo_id.clone())?;
+
+        // Find the ID of the new repository, since it's derived from the URL if not given.
+        let repo_id = repo_id.or_else(|| {
+            trane
+                .list_repos()
+                .into_iter()
+                .find(|repo| repo.url == url)
+                .map(|repo| repo.id)
+        });
+        let Some(repo_id) = repo_id else {
+            return Ok(());
+        };
+        if let Some(git_ref) = git_ref {
+            if let Err(err) = checkout_ref(&self.repo_dir(&repo_id), git_ref) {
+                self.trane.as_mut().unwrap().remove_repo(&repo_id)?;
+                return Err(err);
+            }
+            self.repository_refs
+                .as_mut()
+                .unwrap()
+                .pin(&repo_id, git_ref)?;
+        }
+        self.repository_updates
+            .as_mut()
+            .unwrap()
+            .record_update(&repo_id, Utc::now().timestamp())?;
+        Ok(())
+    }
+
+    /// Removes the given repository from the Trane instance.
+    pub fn remove_repo(&mut self, repo_id: &str) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+        self.trane.as_mut().unwrap().remove_repo(repo_id)?;
+        self.repository_updates.as_mut().unwrap().remove(repo_id)?;
+        self.repository_refs.as_mut().unwrap().remove(repo_id)?;
+        Ok(())
+    }
+
+    /// Lists all the repositories managed by the Trane instance, along with the ref to which they
+    /// are pinned. If `verbose` is true, the state of each local checkout and the time of its last
+    /// successful update are shown as well.
+    pub fn list_repos(&self, verbose: bool) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
+        let trane = self.trane.as_ref().unwrap();
+        let repos = trane.list_repos();
+        if repos.is_empty() {
+            println!("No repositories are managed by Trane");
+            return Ok(());
+        }
+
+        let repository_refs = self.repository_refs.as_ref().unwrap();
+        let git_refs: Vec<&str> = repos
+            .iter()
+            .map(|repo| repository_refs.get(&repo.id).unwrap_or("default"))
+            .collect();
+        let ref_width = column_width("Ref", git_refs.iter().copied(), 41, 20);
+        if !verbose {
+            let id_width = column_width(
+                "ID",
+                repos.iter().map(|repo| repo.id.as_str()),
+                ref_width + 22,
+                20,
+            );
+            println!("{:<id_width$} {:<ref_width$} URL", "ID", "Ref");
+            for (repo, git_ref) in repos.iter().zip(&git_refs) {
+                println!("{:<id_width$} {git_ref:<ref_width$} {}", repo.id, repo.url);
+            }
+            return Ok(());
+        }
+
+        let repository_updates = self.repository_updates.as_ref().unwrap();
+        let statuses: Vec<String> = repos
+            .iter()
+            .map(|repo| sync_status(&self.repo_dir(&repo.id)))
+            .collect();
+        let status_width = column_width("Status", statuses.iter().map(String::as_str), 62, 12);
+        let id_width = column_width(
+            "ID",
+            repos.iter().map(|repo| repo.id.as_str()),
+            ref_width + status_width + 43,
+            20,
+        );
+        println!(
+            "{:<id_width$} {:<ref_width$} {:<status_width$} {:<19} URL",
+            "ID", "Ref", "Status", "Last Updated"
+        );
+        for ((repo, git_ref), status) in repos.iter().zip(&git_refs).zip(&statuses) {
+            let last_updated = repository_updates
+                .last_updated(&repo.id)
+                .and_then(|timestamp| Local.timestamp_opt(timestamp, 0).earliest())
+                .map_or_else(
+                    || "unknown".to_string(),
+                    |dt| dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+                );
+            println!(
+                "{:<id_width$} {git_ref:<ref_width$} {status:<status_width$} {last_updated:<19} {}",
+                repo.id, repo.url
+            );
+        }
+        Ok(())
+    }
+
+    /// Updates the given repository, checking out its pinned ref afterwards.
+    pub fn update_repo(&mut self, repo_id: &str) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+        self.update_repo_with_retries(repo_id)
+    }
+
+    /// Updates the given repository and checks out its pinned ref. Updates that fail because of
+    /// the network are retried a few times with an increasing delay, while other errors are
+    /// returned right away.
+    fn update_repo_with_retries(&mut self, repo_id: &str) -> Result<()> {
+        let mut attempt = 1;
+        let mut delay = UPDATE_RETRY_DELAY;
+        loop {
+            let Err(err) = self.trane.as_mut().unwrap().update_repo(repo_id) else {
+                break;
+            };
+            let err = anyhow::Error::from(err);
+            if !is_network_error(&err) {
+                return Err(err.context(format!("failed to update repository {repo_id}")));
+            }
+            if attempt == MAX_UPDATE_ATTEMPTS {
+                return Err(err.context(format!(
+                    "failed to update repository {repo_id} because of a network error, gave up \
+                    after {attempt} attempts"
+                )));
+            }
+            print_warning(&format!(
+                "network error while updating repository {repo_id}, retrying in {} seconds: {}",
+                delay.as_secs(),
+                err.root_cause()
+            ));
+            sleep(delay);
+            attempt += 1;
+            delay *= 2;
+        }
+
+        self.checkout_pinned_ref(repo_id).with_context(|| {
+            format!("updated repository {repo_id} but failed to check out its pinned ref")
+        })?;
+        self.repository_updates
+            .as_mut()
+            .unwrap()
+            .record_update(repo_id, Utc::now().timestamp())?;
+        Ok(())
+    }
+
+    /// Updates all the repositories managed by the Trane instance, checking out their pinned refs
+    /// afterwards. A repository that fails to update does not stop the others, and the failures
+    /// are reported together at the end.
+    pub fn update_all_repos(&mut self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+        let mut repo_ids: Vec<String> = self
+            .trane
+            .as_ref()
+            .unwrap()
+            .list_repos()
+            .into_iter()
+            .map(|repo| repo.id)
+            .collect();
+        repo_ids.sort();
+
+        let mut failed_ids = Vec::new();
+        for repo_id in &repo_ids {
+            if let Err(err) = self.update_repo_with_retries(repo_id) {
+                eprintln!("Error: {err:#}");
+                failed_ids.push(repo_id.as_str());
+            }
+        }
+        ensure!(
+            failed_ids.is_empty(),
+            "failed to update {} of {} repositories: {}",
+            failed_ids.len(),
+            repo_ids.len(),
+            failed_ids.join(", ")
+        );
+        Ok(())
+    }
+
+    /// Fetches every managed repository and prints whether an update is available for it, without
+    /// changing the local checkouts.
+    pub fn check_repo_updates(&self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        let repos = self.trane.as_ref().unwrap().list_repos();
+        if repos.is_empty() {
+            println!("No repositories are managed by Trane");
+            return Ok(());
+        }
+
+        let mut rows = Vec::new();
+        for repo in &repos {
+            let repo_dir = self.repo_dir(&repo.id);
+            let (status, available) = if !repo_dir.exists() {
+                ("missing".to_string(), "unknown")
+            } else if let Err(err) = fetch(&repo_dir) {
+                eprintln!("Failed to fetch repository with ID {}: {err}", repo.id);
+                ("fetch failed".to_string(), "unknown")
+            } else {
+                let available = match commits_behind(&repo_dir) {
+                    Some(0) => "no",
+                    Some(_) => "yes",
+                    None => "unknown",
+                };
+                (sync_status(&repo_dir), available)
+            };
+            rows.push((repo.id.as_str(), status, available));
+        }
+
+        let status_width = column_width("Status", rows.iter().map(|row| row.1.as_str()), 38, 20);
+        let id_width = column_width("ID", rows.iter().map(|row| row.0), status_width + 18, 20);
+        println!(
+            "{:<id_width$} {:<status_width$} Update Available",
+            "ID", "Status"
+        );
+        for (repo_id, status, available) in rows {
+            println!("{repo_id:<id_width$} {status:<status_width$} {available}");
+        }
+        Ok(())
+    }
+
+    /// Adds the given units to the review list. The batch is reset only once, after all the units
+    /// have been added.
+    pub fn add_to_review_list(&mut self, unit_ids: &[Ustr]) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+        for unit_id in unit_ids {
+            ensure!(
+                self.unit_exists(*unit_id)?,
+                "unit {} does not exist",
+                unit_id
+            );
+        }
+
+        let trane = self.trane.as_mut().unwrap();
+        let result = unit_ids
+            .iter()
+            .try_for_each(|unit_id| trane.add_to_review_list(*unit_id));
+        self.reset_batch();
+        Ok(result?)
+    }
+
+    /// Removes the given units from the review list. The batch is reset only once, after all the
+    /// units have been removed.
+    pub fn remove_from_review_list(&mut self, unit_ids: &[Ustr]) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        let trane = self.trane.as_mut().unwrap();
+        let result = unit_ids
+            .iter()
+            .try_for_each(|unit_id| trane.remove_from_review_list(*unit_id));
+        self.reset_batch();
+        Ok(result?)
+    }
+
+    /// Lists all the units in the review list.
+    pub fn list_review_list(&self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let entries = self.trane.as_ref().unwrap().get_review_list_entries()?;
+        if self.output_format == OutputFormat::Json {
+            let entries: Vec<_> = entries
+                .iter()
+                .map(|unit_id| {
+                    json!({
+                        "unit_type": self.get_unit_type(*unit_id).ok().map(|ut| ut.to_string()),
+                        "unit_id": unit_id,
+                    })
+                })
+                .collect();
+            return print_json(&json!(entries));
+        }
+        if entries.is_empty() {
+            println!("No entries in the blacklist");
+            return Ok(());
+        }
+
+        let mut pager = Pager::new();
+        pager.println("Review list:")?;
+        pager.println(&format!("{:<10} Unit ID", "Unit Type"))?;
+        for unit_id in entries {
+            let line = match self.get_unit_type(unit_id) {
+                Ok(unit_type) => format!("{unit_type:<10} {unit_id}"),
+                Err(_) => format!("{:<10} {unit_id}", "Unknown"),
+            };
+            if !pager.println(&line)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the first field of the given unit that contains one of the search terms, along with
+    /// its value, to show why the unit matched. Trane only returns the IDs of the matching units, so
+    /// this is a case-insensitive substring check that might not find the match for every result.
+    fn search_match(
+        &self,
+        unit_id: Ustr,
+        terms: &[String],
+        field: Option<SearchField>,
+    ) -> Option<(SearchField, String)> {
+        let trane = self.trane.as_ref().unwrap();
+        let (name, description, metadata) = match trane.get_unit_type(unit_id)? {
+            UnitType::Course => {
+                let manifest = trane.get_course_manifest(unit_id)?;
+                (manifest.name, manifest.description, manifest.metadata)
+            }
+            UnitType::Lesson => {
+                let manifest = trane.get_lesson_manifest(unit_id)?;
+                (manifest.name, manifest.description, manifest.metadata)
+            }
+            UnitType::Exercise => {
+                let manifest = trane.get_exercise_manifest(unit_id)?;
+                (manifest.name, manifest.description, None)
+            }
+        };
+        let metadata = metadata
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|(key, values)| {
+                values
+                    .into_iter()
+                    .map(move |value| format!("{key}:{value}"))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // The ID is checked last because it's the least informative of the fields.
+        let terms: Vec<String> = terms.iter().map(|term| term.to_lowercase()).collect();
+        [
+            (SearchField::Name, name),
+            (SearchField::Description, description.unwrap_or_default()),
+            (SearchField::Metadata, metadata),
+            (SearchField::Id, unit_id.to_string()),
+        ]
+        .into_iter()
+        .filter(|(candidate, _)| field.is_none_or(|field| field == *candidate))
+        .find(|(_, value)| {
+            let value = value.to_lowercase();
+            terms.iter().any(|term| value.contains(term))
+        })
+    }
+
+    /// Shortens the given text to at most `MAX_SNIPPET_LENGTH` characters.
+    fn snippet(text: &str) -> String {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.chars().count() <= MAX_SNIPPET_LENGTH {
+            return text;
+        }
+        let truncated: String = text.chars().take(MAX_SNIPPET_LENGTH - 1).collect();
+        format!("{truncated}…")
+    }
+
+    /// Searches for units which match the given query. The search can be restricted to one field of
+    /// the units and to one type of unit. Trane returns at most 50 results, which can be reduced
+    /// further with `limit`.
+    pub fn search(
+        &self,
+        terms: &[String],
+        field: Option<SearchField>,
+        unit_type: Option<SearchUnitType>,
+        limit: Option<usize>,
+    ) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        ensure!(!terms.is_empty(), "no search terms given");
+
+        let field_prefix = field.map_or(String::new(), |field| format!("{}:", field.index_name()));
+        let query = terms
+            .iter()
+            .map(|s| {
+                let mut quoted = field_prefix.clone();
+                quoted.push('"');
+                quoted.push_str(s);
+                quoted.push('"');
+                quoted
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut results = Vec::new();
+        for unit_id in self.trane.as_ref().unwrap().search(&query)? {
+            let type_of_unit = self.get_unit_type(unit_id)?;
+            if unit_type.is_none_or(|unit_type| unit_type.matches(&type_of_unit)) {
+                results.push((type_of_unit, unit_id));
+            }
+        }
+        if let Some(limit) = limit {
+            results.truncate(limit);
+        }
+
+        if results.is_empty() {
+            println!("No results found");
+            return Ok(());
+        }
+
+        let mut pager = Pager::new();
+        pager.println("Search results:")?;
+        let id_width = column_width(
+            "Unit ID",
+            results.iter().map(|(_, unit_id)| unit_id.as_str()),
+            40,
+            50,
+        );
+        pager.println(&format!(
+            "{:<10} {:<id_width$} Match",
+            "Unit Type", "Unit ID"
+        ))?;
+        for (type_of_unit, unit_id) in results {
+            let matched = self
+                .search_match(unit_id, terms, field)
+                .map(|(field, value)| format!("{}: {}", field.index_name(), Self::snippet(&value)))
+                .unwrap_or_default();
+            if !pager.println(&format!(
+                "{:<10} {:<id_width$} {matched}",
+                type_of_unit.to_string(),
+                unit_id.as_str()
+            ))? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Resets the scheduler options to their default values.
+    pub fn reset_scheduler_options(&mut self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+        self.trane.as_mut().unwrap().reset_scheduler_options();
+        Ok(())
+    }
+
+    /// Returns the current scheduler options.
+    pub fn get_scheduler_options(&self) -> Result<SchedulerOptions> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        Ok(self.trane.as_ref().unwrap().get_scheduler_options())
+    }
+
+    /// Sets the scheduler options. The options are verified before they are set.
+    pub fn set_scheduler_options(&mut self, options: SchedulerOptions) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+        options.verify()?;
+        self.trane.as_mut().unwrap().set_scheduler_options(options);
+        Ok(())
+    }
+
+    /// Shows the current scheduler options.
+    pub fn show_scheduler_options(&self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        let options = self.trane.as_ref().unwrap().get_scheduler_options();
+        println!("{options:#?}");
+        Ok(())
+    }
+
+    /// Clears the study session if it's set.
+    pub fn clear_study_session(&mut self) {
+        if self.filter.is_none() {
+            return;
+        }
+        self.replace_filter(None);
+        self.study_session = None;
+        self.reset_batch();
+    }
+
+    /// Prints the list of all the saved unit filters.
+    pub fn list_study_sessions(&self) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let sessions = self.trane.as_ref().unwrap().list_study_sessions();
+        if sessions.is_empty() {
+            println!("No saved study sessions");
+            return Ok(());
+        }
+
+        println!("Saved study sessions:");
+        let id_width = column_width(
+            "ID",
+            sessions.iter().map(|session| session.0.as_str()),
+            1,
+            30,
+        );
+        println!("{:<id_width$} Description", "ID");
+        for session in sessions {
+            println!("{:<id_width$} {}", session.0, session.1);
+        }
+        Ok(())
+    }
+
+    /// Sets the study session to the saved session with the given ID. Setting a study session
+    /// resets the filter, as only one of the two can be active at a time.
+    pub fn set_study_session(&mut self, session_id: &str) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let saved_session = self
+            .trane
+            .as_ref()
+            .unwrap()
+            .get_study_session(session_id)
+            .ok_or_else(|| anyhow!("no study session with ID {}", session_id))?;
+        self.replace_filter(None);
+        self.study_session = Some(StudySessionData {
+            start_time: Utc::now(),
+            definition: saved_session,
+        });
+        self.reset_batch();
+        Ok(())
+    }
+
+    /// Shows the currently set study session.
+    pub fn show_study_session(&self) {
+        if self.filter.is_none() {
+            println!("No study session is set");
+        } else {
+            println!("Study session:");
+            println!("{:#?}", self.study_session.as_ref().unwrap());
+        }
+    }
+
+    /// Prints the path to the transcription asset for the given exercise.
+    pub fn transcription_path(&self, exercise_id: Ustr) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let trane = self.trane.as_ref().unwrap();
+        let path = trane.transcription_download_path(exercise_id);
+        if let Some(path) = path {
+            println!("Transcription asset download path: {}", path.display());
+        }
+        let alias_path = trane.transcription_download_path_alias(exercise_id);
+        if let Some(alias_path) = alias_path {
+            println!(
+                "Transcription asset download path alias: {}",
+                alias_path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Downloads the transcription asset from the given exercise to the specified directory in the
+    /// user preferences.
+    pub fn download_transcription_asset(&self, exercise_id: Ustr, redownload: bool) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        let exercise_id = self.exercise_id_or_current(exercise_id)?;
+        self.trane
+            .as_ref()
+            .unwrap()
+            .download_transcription_asset(exercise_id, redownload)?;
+        println!("Transcription asset for exercise {exercise_id} downloaded");
+        println!();
+        self.transcription_path(exercise_id)?;
+        Ok(())
+    }
+
+    /// Returns the IDs of the exercises in the given course or lesson that have a transcription
+    /// asset with a link to download.
+    fn transcription_exercise_ids(&self, unit_id: Ustr) -> Result<Vec<Ustr>> {
+        let trane = self.trane.as_ref().unwrap();
+        let lesson_ids = match self.get_unit_type(unit_id)? {
+            UnitType::Course => trane.get_lesson_ids(unit_id).unwrap_or_default(),
+            UnitType::Lesson => vec![unit_id],
+            UnitType::Exercise => bail!("Unit with ID {unit_id} is not a course or lesson"),
+        };
+        Ok(lesson_ids
+            .into_iter()
+            .flat_map(|lesson_id| trane.get_exercise_ids(lesson_id).unwrap_or_default())
+            .filter(|exercise_id| {
+                trane
+                    .get_exercise_manifest(*exercise_id)
+                    .is_some_and(|manifest| {
+                        matches!(
+                            manifest.exercise_asset,
+                            ExerciseAsset::TranscriptionAsset {
+                                external_link: Some(_),
+                                ..
+                            }
+                        )
+                    })
+            })
+            .collect())
+    }
+
+    /// Downloads the transcription assets of all the exercises in the given course or lesson. A
+    /// failed download does not stop the others, and a summary is printed at the end.
+    pub fn download_transcription_assets(&self, unit_id: Ustr, redownload: bool) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        let exercise_ids = self.transcription_exercise_ids(unit_id)?;
+        if exercise_ids.is_empty() {
+            println!("No transcription exercises with a download link in unit {unit_id}");
+            return Ok(());
+        }
+
+        let trane = self.trane.as_ref().unwrap();
+        let (mut num_downloaded, mut num_skipped, mut num_failed) = (0, 0, 0);
+        for (index, exercise_id) in (1..).zip(&exercise_ids) {
+            let progress = format!("[{index}/{}] {exercise_id}", exercise_ids.len());
+            if !redownload && trane.is_transcription_asset_downloaded(*exercise_id) {
+                println!("{progress}: skipped, already downloaded");
+                num_skipped += 1;
+                continue;
+            }
+            match trane.download_transcription_asset(*exercise_id, redownload) {
+                Ok(()) => {
+                    println!("{progress}: downloaded");
+                    num_downloaded += 1;
+                }
+                Err(err) => {
+                    println!("{progress}: failed: {err:#}");
+                    num_failed += 1;
+                }
+            }
+        }
+        println!();
+        println!("Downloaded: {num_downloaded}, skipped: {num_skipped}, failed: {num_failed}");
+        Ok(())
+    }
+
+    /// Prints whether the transcription asset for the given exercise has been downloaded.
+    pub fn is_transcription_asset_downloaded(&self, exercise_id: Ustr) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let exercise_id = self.exercise_id_or_current(exercise_id)?;
+        let trane = self.trane.as_ref().unwrap();
+        let is_downloaded = trane.is_transcription_asset_downloaded(exercise_id);
+        if is_downloaded {
+            println!("Transcription for exercise {exercise_id} is downloaded");
+            println!();
+            self.transcription_path(exercise_id)?;
+        } else {
+            println!("Transcription for exercise {exercise_id} is not downloaded");
+        }
+        Ok(())
+    }
+
+    /// Returns the path to the downloaded transcription asset for the given exercise, if it exists.
+    /// The alias path is used if the asset is not found at the download path, which happens when
+    /// the download directory is mounted at a different location on this machine.
+    fn local_transcription_path(&self, exercise_id: Ustr) -> Option<PathBuf> {
+        let trane = self.trane.as_ref().unwrap();
+        [
+            trane.transcription_download_path(exercise_id),
+            trane.transcription_download_path_alias(exercise_id),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|path| path.exists())
+    }
+
+    /// Opens the downloaded transcription asset for the given exercise with the given player
+    /// command or, if none is given, with the default application for the file. If the asset has
+    /// not been downloaded, it's downloaded first when `download` is true.
+    pub fn play_transcription(
+        &self,
+        exercise_id: Ustr,
+        player: Option<&str>,
+        download: bool,
+    ) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let exercise_id = self.exercise_id_or_current(exercise_id)?;
+        ensure!(
+            self.trane
+                .as_ref()
+                .unwrap()
+                .transcription_download_path(exercise_id)
+                .is_some(),
+            "exercise {exercise_id} does not have a transcription asset to download, or the \
+            download path is not set"
+        );
+        let path = match self.local_transcription_path(exercise_id) {
+            Some(path) => path,
+            None if download => {
+                self.download_transcription_asset(exercise_id, false)?;
+                println!();
+                self.local_transcription_path(exercise_id).ok_or_else(|| {
+                    anyhow!("cannot find the downloaded asset for exercise {exercise_id}")
+                })?
+            }
+            None => {
+                println!(
+                    "Transcription for exercise {exercise_id} is not downloaded. \
+                    Use the --download flag to download it first"
+                );
+                return Ok(());
+            }
+        };
 
-        self.trane
-            .as_mut()
-            .unwrap()
-            .remove_from_blacklist(unit_id)?;
-        self.reset_batch();
+        println!("Playing {}", path.display());
+        match player.map(str::split_whitespace) {
+            Some(mut parts) => {
+                let program = parts
+                    .next()
+                    .ok_or_else(|| anyhow!("the player command is empty"))?;
+                let status = Command::new(program)
+                    .args(parts)
+                    .arg(&path)
+                    .status()
+                    .with_context(|| format!("failed to run player {program}"))?;
+                ensure!(status.success(), "player {program} failed");
+            }
+            None => {
+                open::that(&path).with_context(|| format!("failed to open {}", path.display()))?;
+            }
+        }
         Ok(())
     }
 
-    /// Removes the given unit from the blacklist.
-    pub fn remove_prefix_from_blacklist(&mut self, prefix: &str) -> Result<()> {
+    /// Adds a note to the current exercise.
+    pub fn add_note(&mut self, text: &[String]) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+        ensure!(!text.is_empty(), "no note text given");
 
-        self.trane
+        let exercise_id = self.current_exercise()?.id;
+        self.notes
             .as_mut()
             .unwrap()
-            .remove_prefix_from_blacklist(prefix)?;
-        self.reset_batch();
-        Ok(())
+            .add_note(exercise_id, &text.join(" "))
     }
 
-    /// Adds a new repository to the Trane instance.
-    pub fn add_repo(&mut self, url: &str, repo_id: Option<String>) -> Result<()> {
+    /// Removes all the notes from the current exercise.
+    pub fn clear_notes(&mut self) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
-        self.trane.as_mut().unwrap().add_repo(url, repo_id)?;
-        Ok(())
-    }
+        self.ensure_writable()?;
 
-    /// Removes the given repository from the Trane instance.
-    pub fn remove_repo(&mut self, repo_id: &str) -> Result<()> {
-        ensure!(self.trane.is_some(), "no Trane instance is open");
-        self.trane.as_mut().unwrap().remove_repo(repo_id)?;
-        Ok(())
+        let exercise_id = self.current_exercise()?.id;
+        self.notes.as_mut().unwrap().clear_notes(exercise_id)
     }
 
-    /// Lists all the repositories managed by the Trane instance.
-    pub fn list_repos(&self) -> Result<()> {
+    /// Shows the notes for the current exercise.
+    pub fn show_notes(&self) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
-        let repos = self.trane.as_ref().unwrap().list_repos();
-        if repos.is_empty() {
-            println!("No repositories are managed by Trane");
+
+        let exercise_id = self.current_exercise()?.id;
+        let notes = self.notes.as_ref().unwrap().get_notes(exercise_id);
+        if notes.is_empty() {
+            println!("No notes for exercise {exercise_id}");
             return Ok(());
         }
 
-        println!("{:<20} URL", "ID");
-        for repo in repos {
-            println!("{:<20} {}", repo.id, repo.url);
+        println!("Notes for exercise {exercise_id}:");
+        for note in notes {
+            println!("- {note}");
         }
         Ok(())
     }
 
-    /// Updates the given repository.
-    pub fn update_repo(&mut self, repo_id: &str) -> Result<()> {
+    /// Returns the manifest of the exercise with the given ID.
+    fn get_exercise_manifest(&self, exercise_id: Ustr) -> Result<ExerciseManifest> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
-        self.trane.as_mut().unwrap().update_repo(repo_id)?;
-        Ok(())
+
+        self.trane
+            .as_ref()
+            .unwrap()
+            .get_exercise_manifest(exercise_id)
+            .ok_or_else(|| anyhow!("unit with ID {exercise_id} is not a valid exercise"))
     }
 
-    /// Updates all the repositories managed by the Trane instance.
-    pub fn update_all_repos(&mut self) -> Result<()> {
-        ensure!(self.trane.is_some(), "no Trane instance is open");
-        self.trane.as_mut().unwrap().update_all_repos()?;
-        Ok(())
+    /// Inserts the given exercise into the batch right after the current exercise and displays it.
+    /// The rest of the batch is kept so that practice can continue as normal afterwards.
+    fn practice_exercise(&mut self, manifest: ExerciseManifest) -> Result<()> {
+        self.submit_current_score()?;
+        self.current_score = None;
+
+        if self.batch.is_empty() {
+            self.batch.push(manifest);
+            self.batch_index = 0;
+        } else {
+            self.batch_index += 1;
+            self.batch.insert(self.batch_index, manifest);
+        }
+
+        let manifest = self.current_exercise()?;
+        self.display_exercise(&manifest)
     }
 
-    /// Adds the given unit to the review list.
-    pub fn add_to_review_list(&mut self, unit_id: Ustr) -> Result<()> {
+    /// Adds the given exercise, or the current exercise if the ID is empty, to the bookmarks.
+    pub fn add_bookmark(&mut self, exercise_id: Ustr) -> Result<Ustr> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
-        ensure!(
-            self.unit_exists(unit_id)?,
-            "unit {} does not exist",
-            unit_id
-        );
+        self.ensure_writable()?;
 
-        self.trane.as_mut().unwrap().add_to_review_list(unit_id)?;
-        self.reset_batch();
-        Ok(())
+        let exercise_id = self.exercise_id_or_current(exercise_id)?;
+        self.get_exercise_manifest(exercise_id)?;
+        self.bookmarks.as_mut().unwrap().add_bookmark(exercise_id)?;
+        Ok(exercise_id)
     }
 
-    /// Removes the given unit from the review list.
-    pub fn remove_from_review_list(&mut self, unit_id: Ustr) -> Result<()> {
+    /// Removes the given exercise, or the current exercise if the ID is empty, from the bookmarks.
+    pub fn remove_bookmark(&mut self, exercise_id: Ustr) -> Result<Ustr> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
 
-        self.trane
+        let exercise_id = self.exercise_id_or_current(exercise_id)?;
+        self.bookmarks
             .as_mut()
             .unwrap()
-            .remove_from_review_list(unit_id)?;
-        self.reset_batch();
-        Ok(())
+            .remove_bookmark(exercise_id)?;
+        Ok(exercise_id)
     }
 
-    /// Lists all the units in the review list.
-    pub fn list_review_list(&self) -> Result<()> {
+    /// Lists all the bookmarked exercises.
+    pub fn list_bookmarks(&self) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
-        let entries = self.trane.as_ref().unwrap().get_review_list_entries()?;
-        if entries.is_empty() {
-            println!("No entries in the blacklist");
+        let bookmarks = self.bookmarks.as_ref().unwrap().get_bookmarks();
+        if bookmarks.is_empty() {
+            println!("No bookmarked exercises");
             return Ok(());
         }
 
-        println!("Review list:");
-        println!("{:<10} {:<50}", "Unit Type", "Unit ID");
-        for unit_id in entries {
-            let unit_type = self.get_unit_type(unit_id);
-            if unit_type.is_err() {
-                println!("{:<10} {:<50}", "Unknown", unit_id.as_str());
-            } else {
-                println!("{:<10} {:<50}", unit_type.unwrap(), unit_id.as_str());
-            }
+        println!("Bookmarks:");
+        let id_width = column_width("Exercise ID", bookmarks.iter().map(Ustr::as_str), 26, 50);
+        println!("{:<5} {:<id_width$} Name", "#", "Exercise ID");
+        for (i, exercise_id) in bookmarks.iter().enumerate() {
+            let name = self
+                .get_exercise_manifest(*exercise_id)
+                .map_or_else(|_| "Unknown".to_string(), |manifest| manifest.name);
+            println!("{:<5} {:<id_width$} {}", i + 1, exercise_id.as_str(), name);
         }
         Ok(())
     }
 
-    /// Searches for units which match the given query.
-    pub fn search(&self, terms: &[String]) -> Result<()> {
+    /// Loads the given bookmarked exercise for a one-off attempt, or sets a filter that only shows
+    /// that exercise if `filter` is true. The bookmark is referenced by its position or by the ID
+    /// of the exercise. Returns the ID of the exercise.
+    pub fn goto_bookmark(&mut self, bookmark: &str, filter: bool) -> Result<Ustr> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
-        ensure!(!terms.is_empty(), "no search terms given");
 
-        let query = terms
-            .iter()
-            .map(|s| {
-                let mut quoted = "\"".to_string();
-                quoted.push_str(s);
-                quoted.push('"');
-                quoted
-            })
-            .collect::<Vec<_>>()
-            .join(" ");
-        let results = self.trane.as_ref().unwrap().search(&query)?;
+        let exercise_id = self.bookmarks.as_ref().unwrap().find_bookmark(bookmark)?;
+        if filter {
+            self.filter_exercises(&[exercise_id])?;
+        } else {
+            let manifest = self.get_exercise_manifest(exercise_id)?;
+            self.practice_exercise(manifest)?;
+        }
+        Ok(exercise_id)
+    }
 
-        if results.is_empty() {
-            println!("No results found");
+    /// Prints the exercises the scheduler would select with the current filter, without changing
+    /// the current batch. Since the selection is random, the next batch will not necessarily
+    /// contain the same exercises. Trane also keeps track of how often each exercise is selected,
+    /// so previewing slightly lowers the chance that these exercises are selected again.
+    pub fn preview(&self, count: Option<usize>) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let mut batch = self.new_batch()?;
+        if let Some(count) = count {
+            batch.truncate(count);
+        }
+        if self.output_format == OutputFormat::Json {
+            let exercises: Vec<_> = batch
+                .iter()
+                .map(|manifest| {
+                    json!({
+                        "exercise_id": manifest.id,
+                        "lesson_id": manifest.lesson_id,
+                        "course_id": manifest.course_id,
+                    })
+                })
+                .collect();
+            return print_json(&json!(exercises));
+        }
+        if batch.is_empty() {
+            println!("No exercises match the current filter");
             return Ok(());
         }
 
-        println!("Search results:");
-        println!("{:<10} {:<50}", "Unit Type", "Unit ID");
-        for unit_id in results {
-            let unit_type = self.get_unit_type(unit_id)?;
-            println!("{unit_type:<10} {unit_id:<50}");
+        let mut pager = Pager::new();
+        let course_width = column_width(
+            "Course ID",
+            batch.iter().map(|manifest| manifest.course_id.as_str()),
+            46,
+            40,
+        );
+        let lesson_width = column_width(
+            "Lesson ID",
+            batch.iter().map(|manifest| manifest.lesson_id.as_str()),
+            course_width + 26,
+            40,
+        );
+        pager.println(&format!(
+            "{:<4} {:<course_width$} {:<lesson_width$} Exercise ID",
+            "#", "Course ID", "Lesson ID"
+        ))?;
+        for (position, manifest) in (1..).zip(&batch) {
+            if !pager.println(&format!(
+                "{:<4} {:<course_width$} {:<lesson_width$} {}",
+                position,
+                manifest.course_id.as_str(),
+                manifest.lesson_id.as_str(),
+                manifest.id
+            ))? {
+                break;
+            }
         }
         Ok(())
     }
 
-    /// Resets the scheduler options to their default values.
-    pub fn reset_scheduler_options(&mut self) -> Result<()> {
+    /// Displays the exercise with the given ID so that it can be practiced and scored right away,
+    /// regardless of the current filter. The `next` command continues with the rest of the batch.
+    pub fn practice(&mut self, exercise_id: Ustr) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
-        self.trane.as_mut().unwrap().reset_scheduler_options();
-        Ok(())
+
+        let manifest = self.get_exercise_manifest(exercise_id)?;
+        self.practice_exercise(manifest)
     }
 
-    /// Sets the scheduler options.
-    pub fn set_scheduler_options(&mut self, options: SchedulerOptions) -> Result<()> {
+    /// Sets the filter to only show exercises from the given course or lesson and shows the first
+    /// exercise of a new batch. The filter is left unchanged if the unit has no exercises or all of
+    /// them are in the blacklist.
+    pub fn practice_unit(&mut self, unit_id: Ustr, unit_type: &UnitType) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
-        self.trane.as_mut().unwrap().set_scheduler_options(options);
-        Ok(())
+
+        let kind = unit_type.to_string().to_lowercase();
+        if self.get_unit_type(unit_id)? != *unit_type {
+            bail!("Unit with ID {unit_id} is not a {kind}");
+        }
+        let exercise_ids = self.unit_exercise_ids(unit_id, unit_type);
+        ensure!(
+            !exercise_ids.is_empty(),
+            "{kind} {unit_id} has no exercises"
+        );
+        let mut all_blacklisted = true;
+        for exercise_id in exercise_ids {
+            if !self.exercise_blacklisted(exercise_id)? {
+                all_blacklisted = false;
+                break;
+            }
+        }
+        ensure!(
+            !all_blacklisted,
+            "all the exercises in {kind} {unit_id} are in the blacklist"
+        );
+
+        match unit_type {
+            UnitType::Course => self.filter_courses(&[unit_id], false)?,
+            UnitType::Lesson => self.filter_lessons(&[unit_id])?,
+            UnitType::Exercise => bail!("only courses and lessons can be practiced this way"),
+        }
+        self.next()
     }
 
-    /// Shows the current scheduler options.
-    pub fn show_scheduler_options(&self) -> Result<()> {
+    /// Returns whether the given exercise, its lesson, or its course are in the blacklist.
+    fn exercise_blacklisted(&self, exercise_id: Ustr) -> Result<bool> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
-        let options = self.trane.as_ref().unwrap().get_scheduler_options();
-        println!("{options:#?}");
-        Ok(())
-    }
 
-    /// Clears the study session if it's set.
-    pub fn clear_study_session(&mut self) {
-        if self.filter.is_none() {
-            return;
+        let trane = self.trane.as_ref().unwrap();
+        let lesson_id = trane.get_exercise_lesson(exercise_id);
+        let course_id = lesson_id.and_then(|lesson_id| trane.get_lesson_course(lesson_id));
+        for unit_id in [Some(exercise_id), lesson_id, course_id]
+            .into_iter()
+            .flatten()
+        {
+            if trane.blacklisted(unit_id)? {
+                return Ok(true);
+            }
         }
-        self.filter = None;
-        self.study_session = None;
-        self.reset_batch();
+        Ok(false)
     }
 
-    /// Prints the list of all the saved unit filters.
-    pub fn list_study_sessions(&self) -> Result<()> {
+    /// Prints an estimate of how many exercises will need to be reviewed on each of the next
+    /// `num_days` days. Trane does not schedule exercises by due date, so an exercise is considered
+    /// due on the first day its score, decayed by the scorer to account for the passage of time,
+    /// falls below the passing score.
+    pub fn show_forecast(&self, num_days: usize) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
+        ensure!(num_days > 0, "the number of days must be greater than zero");
 
-        let sessions = self.trane.as_ref().unwrap().list_study_sessions();
-        if sessions.is_empty() {
-            println!("No saved study sessions");
-            return Ok(());
+        let trane = self.trane.as_ref().unwrap();
+        let options = trane.get_scheduler_options();
+        let passing_score = options.passing_score.compute_score(0);
+        let scorer = SimpleScorer {};
+
+        // Simulate the passage of time by moving the timestamps of the trials into the past.
+        let mut due_now = 0;
+        let mut due_per_day = vec![0; num_days];
+        for exercise_id in trane.get_all_exercise_ids(None) {
+            if self.exercise_blacklisted(exercise_id)? {
+                continue;
+            }
+            let trials = trane.get_scores(exercise_id, options.num_trials)?;
+            if trials.is_empty() {
+                continue;
+            }
+            if scorer.score(&trials)? < passing_score {
+                due_now += 1;
+                continue;
+            }
+
+            for (day, count) in (1_i64..).zip(due_per_day.iter_mut()) {
+                let offset = day * SECONDS_PER_DAY;
+                let shifted_trials: Vec<ExerciseTrial> = trials
+                    .iter()
+                    .map(|trial| ExerciseTrial {
+                        score: trial.score,
+                        timestamp: trial.timestamp - offset,
+                    })
+                    .collect();
+                if scorer.score(&shifted_trials)? < passing_score {
+                    *count += 1;
+                    break;
+                }
+            }
         }
 
-        println!("Saved study sessions:");
-        println!("{:<30} {:<50}", "ID", "Description");
-        for filter in sessions {
-            println!("{:<30} {:<50}", filter.0, filter.1);
+        // Print the forecast.
+        println!("Review forecast (approximation based on the decay of exercise scores):");
+        println!("Passing score: {passing_score:.2}");
+        println!("Exercises already below the passing score: {due_now}");
+        println!();
+        println!("{:<12} {:>6}", "Date", "Due");
+        let max_count = due_per_day.iter().copied().max().unwrap_or_default();
+        let today = Local::now();
+        for (day, count) in (1_i64..).zip(due_per_day.iter()) {
+            let date = today + Duration::days(day);
+            println!(
+                "{:<12} {:>6} {}",
+                date.format("%Y-%m-%d"),
+                count,
+                ascii_bar(*count, max_count, MAX_BAR_WIDTH)
+            );
         }
         Ok(())
     }
 
-    /// Sets the study session to the saved session with the given ID. Setting a study session
-    /// resets the filter, as only one of the two can be active at a time.
-    pub fn set_study_session(&mut self, session_id: &str) -> Result<()> {
+    /// Formats a row of the table printed by `show_overview`.
+    fn format_overview_row(
+        label: &str,
+        label_width: usize,
+        num_exercises: usize,
+        num_scored: usize,
+        total_score: f32,
+        num_mastered: usize,
+    ) -> String {
+        let average = if num_scored == 0 {
+            "N/A".to_string()
+        } else {
+            format!("{:.2}", total_score / num_scored as f32)
+        };
+        format!(
+            "{label:<label_width$} {num_exercises:>9} {num_scored:>7} {average:>9} \
+            {num_mastered:>8}"
+        )
+    }
+
+    /// Prints a summary of the progress in each course: the number of exercises, how many of them
+    /// have been scored, their average score, and how many have a score of at least `threshold`.
+    /// To keep this fast on large libraries, the score of each exercise is computed only from its
+    /// most recent scores, up to the number of trials used by the scheduler, so the averages are an
+    /// approximation for exercises with longer histories.
+    pub fn show_overview(&self, threshold: f32) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
+        ensure!(
+            (1.0..=5.0).contains(&threshold),
+            "the threshold must be between 1 and 5"
+        );
 
-        let saved_session = self
-            .trane
-            .as_ref()
-            .unwrap()
-            .get_study_session(session_id)
-            .ok_or_else(|| anyhow!("no study session with ID {}", session_id))?;
-        self.filter = None;
-        self.study_session = Some(StudySessionData {
-            start_time: Utc::now(),
-            definition: saved_session,
-        });
-        self.reset_batch();
+        let trane = self.trane.as_ref().unwrap();
+        let course_ids = trane.get_course_ids();
+        if course_ids.is_empty() {
+            println!("No courses in library");
+            return Ok(());
+        }
+
+        let num_trials = trane.get_scheduler_options().num_trials;
+        let scorer = SimpleScorer {};
+        let mut pager = Pager::new();
+        let id_width = column_width("Course ID", course_ids.iter().map(Ustr::as_str), 38, 40);
+        pager.println(&format!(
+            "{:<id_width$} {:>9} {:>7} {:>9} {:>8}",
+            "Course ID", "Exercises", "Scored", "Avg Score", "Mastered"
+        ))?;
+        let (mut all_exercises, mut all_scored, mut all_score, mut all_mastered) = (0, 0, 0.0, 0);
+        for course_id in course_ids {
+            let exercise_ids = trane.get_all_exercise_ids(Some(course_id));
+            let (mut num_scored, mut total_score, mut num_mastered) = (0, 0.0, 0);
+            for exercise_id in &exercise_ids {
+                let trials = trane.get_scores(*exercise_id, num_trials)?;
+                if trials.is_empty() {
+                    continue;
+                }
+                let score = scorer.score(&trials)?;
+                num_scored += 1;
+                total_score += score;
+                if score >= threshold {
+                    num_mastered += 1;
+                }
+            }
+
+            all_exercises += exercise_ids.len();
+            all_scored += num_scored;
+            all_score += total_score;
+            all_mastered += num_mastered;
+            if !pager.println(&Self::format_overview_row(
+                &course_id,
+                id_width,
+                exercise_ids.len(),
+                num_scored,
+                total_score,
+                num_mastered,
+            ))? {
+                return Ok(());
+            }
+        }
+
+        pager.println("")?;
+        pager.println(&Self::format_overview_row(
+            "Total",
+            id_width,
+            all_exercises,
+            all_scored,
+            all_score,
+            all_mastered,
+        ))?;
+        pager.println(&format!(
+            "Mastered exercises have a score of at least {threshold:.2}. Scores are computed from \
+            the {num_trials} most recent trials of each exercise"
+        ))?;
         Ok(())
     }
 
-    /// Shows the currently set study session.
-    pub fn show_study_session(&self) {
-        if self.filter.is_none() {
-            println!("No study session is set");
-        } else {
-            println!("Study session:");
-            println!("{:#?}", self.study_session.as_ref().unwrap());
-        }
+    /// Returns the IDs of the exercises selected by the current course or lesson filter, or all
+    /// the exercises in the library if no filter is set.
+    fn filtered_exercise_ids(&self) -> Result<Vec<Ustr>> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+
+        let trane = self.trane.as_ref().unwrap();
+        let unit_ids = match &self.filter {
+            None => return Ok(trane.get_all_exercise_ids(None)),
+            Some(UnitFilter::CourseFilter { course_ids }) => course_ids,
+            Some(UnitFilter::LessonFilter { lesson_ids }) => lesson_ids,
+            Some(_) => bail!("only course and lesson filters are supported"),
+        };
+        let mut exercise_ids: Vec<Ustr> = unit_ids
+            .iter()
+            .flat_map(|unit_id| trane.get_all_exercise_ids(Some(*unit_id)))
+            .collect();
+        exercise_ids.sort();
+        exercise_ids.dedup();
+        Ok(exercise_ids)
     }
 
-    /// Prints the path to the transcription asset for the given exercise.
-    pub fn transcription_path(&self, exercise_id: Ustr) -> Result<()> {
+    /// Prints an estimate of how many successful reviews are needed for every exercise selected by
+    /// the current filter to reach the target score. The number of reviews for each exercise is
+    /// found by adding perfect scores to its trials until the scorer reaches the target.
+    pub fn show_remaining(&self, target: f32) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
+        ensure!(
+            (1.0..=5.0).contains(&target),
+            "the target score must be between 1 and 5"
+        );
 
         let trane = self.trane.as_ref().unwrap();
-        let path = trane.transcription_download_path(exercise_id);
-        if let Some(path) = path {
-            println!("Transcription asset download path: {}", path.display());
+        let num_trials = trane.get_scheduler_options().num_trials;
+        let scorer = SimpleScorer {};
+        let now = Utc::now().timestamp();
+
+        let mut num_exercises = 0;
+        let mut num_mastered = 0;
+        let mut num_unreachable = 0;
+        let mut total_reviews = 0;
+        for exercise_id in self.filtered_exercise_ids()? {
+            if self.exercise_blacklisted(exercise_id)? {
+                continue;
+            }
+            num_exercises += 1;
+
+            // Trials are sorted from most to least recent, so new reviews go to the front.
+            let mut trials = trane.get_scores(exercise_id, num_trials)?;
+            let mut num_reviews = 0;
+            while scorer.score(&trials)? < target && num_reviews < MAX_SIMULATED_REVIEWS {
+                trials.insert(
+                    0,
+                    ExerciseTrial {
+                        score: 5.0,
+                        timestamp: now,
+                    },
+                );
+                num_reviews += 1;
+            }
+
+            if num_reviews == 0 {
+                num_mastered += 1;
+            } else if scorer.score(&trials)? < target {
+                num_unreachable += 1;
+            }
+            total_reviews += num_reviews;
         }
-        let alias_path = trane.transcription_download_path_alias(exercise_id);
-        if let Some(alias_path) = alias_path {
+
+        if num_exercises == 0 {
+            println!("No exercises match the current filter");
+            return Ok(());
+        }
+        println!("Estimated remaining reviews (assuming a perfect score on every review):");
+        println!("Target score: {target:.2}");
+        println!("Exercises: {num_exercises}");
+        println!("Exercises already at the target: {num_mastered}");
+        println!("Estimated reviews remaining: {total_reviews}");
+        if num_unreachable > 0 {
             println!(
-                "Transcription asset download path alias: {}",
-                alias_path.display()
+                "Exercises that did not reach the target after {MAX_SIMULATED_REVIEWS} reviews: \
+                {num_unreachable}"
             );
         }
         Ok(())
     }
 
-    /// Downloads the transcription asset from the given exercise to the specified directory in the
-    /// user preferences.
-    pub fn download_transcription_asset(&self, exercise_id: Ustr, redownload: bool) -> Result<()> {
+    /// Lists the instruments for which transcription exercises are generated.
+    pub fn list_instruments(&self) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
 
-        let exercise_id = self.exercise_id_or_current(exercise_id)?;
-        self.trane
-            .as_ref()
-            .unwrap()
-            .download_transcription_asset(exercise_id, redownload)?;
-        println!("Transcription asset for exercise {exercise_id} downloaded");
-        println!();
-        self.transcription_path(exercise_id)?;
+        let preferences = self.trane.as_ref().unwrap().get_user_preferences()?;
+        let instruments = preferences.transcription.unwrap_or_default().instruments;
+        if instruments.is_empty() {
+            println!("No instruments are set for transcription courses");
+            return Ok(());
+        }
+
+        let id_width = column_width(
+            "ID",
+            instruments.iter().map(|instrument| instrument.id.as_str()),
+            21,
+            30,
+        );
+        println!("{:<id_width$} Name", "ID");
+        for instrument in instruments {
+            println!("{:<id_width$} {}", instrument.id, instrument.name);
+        }
         Ok(())
     }
 
-    /// Prints whether the transcription asset for the given exercise has been downloaded.
-    pub fn is_transcription_asset_downloaded(&self, exercise_id: Ustr) -> Result<()> {
+    /// Adds an instrument to the list of instruments for which transcription exercises are
+    /// generated. The changes take effect the next time the library is opened.
+    pub fn add_instrument(&mut self, id: &str, name: &[String]) -> Result<()> {
         ensure!(self.trane.is_some(), "no Trane instance is open");
-
-        let exercise_id = self.exercise_id_or_current(exercise_id)?;
-        let trane = self.trane.as_ref().unwrap();
-        let is_downloaded = trane.is_transcription_asset_downloaded(exercise_id);
-        if is_downloaded {
-            println!("Transcription for exercise {exercise_id} is downloaded");
-            println!();
-            self.transcription_path(exercise_id)?;
-        } else {
-            println!("Transcription for exercise {exercise_id} is not downloaded");
+        self.ensure_writable()?;
+        ensure!(!id.is_empty(), "the instrument ID cannot be empty");
+        ensure!(!name.is_empty(), "no instrument name given");
+
+        // The instrument ID is used to generate lesson IDs, so warn about unusual characters.
+        if !id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            print_warning(&format!(
+                "instrument ID {id} contains characters other than lowercase letters, digits, \
+                and underscores"
+            ));
         }
+
+        let trane = self.trane.as_mut().unwrap();
+        let mut preferences = trane.get_user_preferences()?;
+        let mut transcription = preferences.transcription.unwrap_or_default();
+        ensure!(
+            !transcription.instruments.iter().any(|i| i.id == id),
+            "instrument with ID {id} already exists"
+        );
+        transcription.instruments.push(Instrument {
+            id: id.to_string(),
+            name: name.join(" "),
+        });
+        preferences.transcription = Some(transcription);
+        trane.set_user_preferences(preferences)?;
+        Ok(())
+    }
+
+    /// Removes an instrument from the list of instruments for which transcription exercises are
+    /// generated. The changes take effect the next time the library is opened.
+    pub fn remove_instrument(&mut self, id: &str) -> Result<()> {
+        ensure!(self.trane.is_some(), "no Trane instance is open");
+        self.ensure_writable()?;
+
+        let trane = self.trane.as_mut().unwrap();
+        let mut preferences = trane.get_user_preferences()?;
+        let mut transcription = preferences.transcription.unwrap_or_default();
+        let num_instruments = transcription.instruments.len();
+        transcription.instruments.retain(|i| i.id != id);
+        ensure!(
+            transcription.instruments.len() < num_instruments,
+            "no instrument with ID {id}"
+        );
+        preferences.transcription = Some(transcription);
+        trane.set_user_preferences(preferences)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::TraneCli;
+    use clap::Parser;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+
+    /// Creates a library with a single course, lesson, and exercise in a new directory inside the
+    /// temporary directory and returns its path.
+    fn create_library(name: &str) -> Result<PathBuf> {
+        let library_root =
+            std::env::temp_dir().join(format!("trane-cli-{}-{name}", std::process::id()));
+        let lesson_dir = library_root.join("course").join("lesson");
+        let exercise_dir = lesson_dir.join("exercise");
+        create_dir_all(&exercise_dir)?;
+        write(
+            library_root.join("course").join(COURSE_MANIFEST_FILENAME),
+            r#"{"id": "course", "name": "Course", "dependencies": []}"#,
+        )?;
+        write(
+            lesson_dir.join("lesson_manifest.json"),
+            r#"{"id": "lesson", "course_id": "course", "name": "Lesson", "dependencies": []}"#,
+        )?;
+        write(
+            exercise_dir.join("exercise_manifest.json"),
+            r#"{"id": "exercise", "lesson_id": "lesson", "course_id": "course",
+            "name": "Exercise", "exercise_type": "Procedural",
+            "exercise_asset": {"FlashcardAsset": {"front_path": "front.md"}}}"#,
+        )?;
+        write(exercise_dir.join("front.md"), "Front")?;
+        Ok(library_root)
+    }
+
+    /// Parses and executes the given command line.
+    fn execute(app: &mut TraneApp, line: &str) -> Result<bool> {
+        let cli = TraneCli::try_parse_from(std::iter::once("trane").chain(line.split(' ')))?;
+        cli.execute_subcommand(app)
+    }
+
+    /// Verifies that a batch reset made while auto-next is off does not show a new exercise once
+    /// auto-next is turned on.
+    #[test]
+    fn auto_next_ignores_earlier_resets() -> Result<()> {
+        let library_root = create_library("auto_next")?;
+        let mut app = TraneApp::default();
+        app.open_library(library_root.to_str().unwrap())?;
+
+        execute(&mut app, "auto-next off")?;
+        execute(&mut app, "next")?;
+        assert!(!app.batch.is_empty());
+        execute(&mut app, "reset-batch")?;
+        assert!(app.batch.is_empty());
+        execute(&mut app, "auto-next on")?;
+        assert!(app.batch.is_empty());
+
+        // A reset made by the command itself still shows a new exercise.
+        execute(&mut app, "reset-batch")?;
+        assert!(!app.batch.is_empty());
+
+        drop(app);
+        remove_dir_all(&library_root)?;
         Ok(())
     }
 }
@@ -0,0 +1,84 @@
+//! Contains the logic to store the exercises the user has bookmarked. Unlike the review list,
+//! bookmarks have no effect on scheduling.
+
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+use ustr::Ustr;
+
+use crate::json_file::{read_json, write_json};
+
+/// The name of the file inside the `.trane` directory where the bookmarks are stored.
+pub const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+/// Stores the IDs of the bookmarked exercises in a JSON file inside the library's config
+/// directory.
+pub(crate) struct Bookmarks {
+    /// The path to the JSON file containing the bookmarks.
+    path: PathBuf,
+
+    /// The IDs of the bookmarked exercises, in the order in which they were added.
+    exercise_ids: Vec<Ustr>,
+}
+
+impl Bookmarks {
+    /// Opens the bookmarks stored in the given config directory, creating an empty list if the
+    /// file does not exist yet.
+    pub fn new_from_disk(config_dir: &Path) -> Result<Self> {
+        let path = config_dir.join(BOOKMARKS_FILE);
+        let exercise_ids = read_json(&path, "bookmarks")?.unwrap_or_default();
+        Ok(Self { path, exercise_ids })
+    }
+
+    /// Writes the bookmarks to disk.
+    fn save(&self) -> Result<()> {
+        write_json(&self.path, &self.exercise_ids, "bookmarks")
+    }
+
+    /// Returns the IDs of all the bookmarked exercises.
+    pub fn get_bookmarks(&self) -> &[Ustr] {
+        &self.exercise_ids
+    }
+
+    /// Returns the ID of the bookmark with the given position, starting at one.
+    pub fn get_bookmark(&self, position: usize) -> Result<Ustr> {
+        match position
+            .checked_sub(1)
+            .and_then(|i| self.exercise_ids.get(i))
+        {
+            Some(exercise_id) => Ok(*exercise_id),
+            None => bail!("no bookmark at position {position}"),
+        }
+    }
+
+    /// Returns the ID of the bookmark referenced by the given string, which is either a position, as
+    /// accepted by `get_bookmark`, or the ID of a bookmarked exercise.
+    pub fn find_bookmark(&self, bookmark: &str) -> Result<Ustr> {
+        if let Ok(position) = bookmark.parse::<usize>() {
+            return self.get_bookmark(position);
+        }
+        let exercise_id = Ustr::from(bookmark);
+        if !self.exercise_ids.contains(&exercise_id) {
+            bail!("exercise {exercise_id} is not bookmarked");
+        }
+        Ok(exercise_id)
+    }
+
+    /// Adds the given exercise to the bookmarks. Adding an exercise that is already bookmarked has
+    /// no effect.
+    pub fn add_bookmark(&mut self, exercise_id: Ustr) -> Result<()> {
+        if self.exercise_ids.contains(&exercise_id) {
+            return Ok(());
+        }
+        self.exercise_ids.push(exercise_id);
+        self.save()
+    }
+
+    /// Removes the given exercise from the bookmarks.
+    pub fn remove_bookmark(&mut self, exercise_id: Ustr) -> Result<()> {
+        let Some(index) = self.exercise_ids.iter().position(|id| *id == exercise_id) else {
+            bail!("exercise {exercise_id} is not bookmarked");
+        };
+        self.exercise_ids.remove(index);
+        self.save()
+    }
+}
@@ -1,15 +1,17 @@
 //! Contains the logic to parse and execute command-line instructions.
 
 use anyhow::{anyhow, Result};
-use clap::{Parser, Subcommand};
+use clap::{Command, CommandFactory, Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
 use std::{path::Path, str::FromStr};
-use trane::data::{filter::FilterOp, SchedulerOptions};
+use trane::data::{filter::FilterOp, PassingScoreOptions, UnitType};
 use ustr::Ustr;
 
-use crate::app::TraneApp;
+use crate::app::{OutputFormat, TraneApp};
 
 /// A key-value pair used to parse course and lesson metadata from the command-line. Pairs are
-/// written in the format `<key>:<value>`. Multiple pairs are separated by spaces.
+/// written in the format `<key>:<value>`. Multiple pairs are separated by spaces. The pair is split
+/// at the first colon, so keys cannot contain colons but values can.
 #[derive(Clone, Debug)]
 pub(crate) struct KeyValue {
     pub key: String,
@@ -21,28 +23,97 @@ impl FromStr for KeyValue {
 
     /// Parse a string value into a key-value pair.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let key_value: Vec<&str> = s.trim().split(':').collect();
-        if key_value.len() != 2 {
+        let Some((key, value)) = s.trim().split_once(':') else {
             return Err(anyhow!("Invalid key-value pair"));
-        }
-        if key_value[0].is_empty() || key_value[1].is_empty() {
+        };
+        if key.is_empty() || value.is_empty() {
             return Err(anyhow!("Invalid key-value pair"));
         }
 
         Ok(KeyValue {
-            key: key_value[0].to_string(),
-            value: key_value[1].to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
         })
     }
 }
 
+/// The names and short descriptions of the mastery scores, in order from one to five.
+const SCORE_LEVELS: [(&str, &str); 5] = [
+    ("bad", "barely any mastery, still figuring out the exercise"),
+    (
+        "poor",
+        "some mastery, can do the exercise slowly with some mistakes",
+    ),
+    (
+        "ok",
+        "significant mastery, can do the exercise slowly with barely any mistakes",
+    ),
+    (
+        "good",
+        "mastery, can do the exercise at full speed with little conscious thought",
+    ),
+    (
+        "great",
+        "total mastery, can do the exercise effortlessly and come up with variations",
+    ),
+];
+
+/// A mastery score given on the command-line, either as a number from 1 to 5 or as the name of
+/// its level (bad, poor, ok, good, or great).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ScoreArg {
+    pub value: u8,
+}
+
+impl ScoreArg {
+    /// Returns the name and description of the score's level.
+    fn level(self) -> (&'static str, &'static str) {
+        SCORE_LEVELS[usize::from(self.value - 1)]
+    }
+
+    /// Returns a one-line reminder of what the score means.
+    pub fn reminder(self) -> String {
+        let (name, description) = self.level();
+        format!("Score {} ({name}): {description}", self.value)
+    }
+}
+
+impl FromStr for ScoreArg {
+    type Err = anyhow::Error;
+
+    /// Parse a number from 1 to 5 or the name of a level into a score.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(value) = s.parse::<u8>() {
+            if (1..=5).contains(&value) {
+                return Ok(ScoreArg { value });
+            }
+        }
+        (1..)
+            .zip(SCORE_LEVELS)
+            .find(|(_, (name, _))| name.eq_ignore_ascii_case(s))
+            .map(|(value, _)| ScoreArg { value })
+            .ok_or_else(|| {
+                let names: Vec<&str> = SCORE_LEVELS.iter().map(|(name, _)| *name).collect();
+                anyhow!(
+                    "invalid score {s}, valid values are 1-5 or {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
 /// Contains subcommands for manipulating the unit blacklist.
 #[derive(Clone, Debug, Subcommand)]
 pub(crate) enum BlacklistSubcommands {
-    #[clap(about = "Add the given unit to the blacklist")]
+    #[clap(
+        about = "Add the given units to the blacklist. The batch is reset once after all the \
+        units are added"
+    )]
     Add {
-        #[clap(help = "The ID of the unit")]
-        unit_id: Ustr,
+        #[clap(help = "The IDs of the units")]
+        #[clap(required = true)]
+        unit_ids: Vec<Ustr>,
     },
 
     #[clap(about = "Add the current exercise's course to the blacklist")]
@@ -51,16 +122,32 @@ pub(crate) enum BlacklistSubcommands {
     #[clap(about = "Add the current exercise to the blacklist")]
     Exercise,
 
+    #[clap(about = "Write the units in the blacklist to a file, one per line")]
+    Export {
+        #[clap(help = "The path to the file")]
+        path: String,
+    },
+
+    #[clap(about = "Add the units listed in a file, one per line, to the blacklist")]
+    Import {
+        #[clap(help = "The path to the file")]
+        path: String,
+    },
+
     #[clap(about = "Add the current exercise's lesson to the blacklist")]
     Lesson,
 
     #[clap(about = "List the units currently in the blacklist")]
     List,
 
-    #[clap(about = "Remove unit from the blacklist")]
+    #[clap(
+        about = "Remove units from the blacklist. The batch is reset once after all the \
+        units are removed"
+    )]
     Remove {
-        #[clap(help = "The unit to remove from the blacklist")]
-        unit_id: Ustr,
+        #[clap(help = "The units to remove from the blacklist")]
+        #[clap(required = true)]
+        unit_ids: Vec<Ustr>,
     },
 
     #[clap(about = "Removes all the units that match the given prefix from the blacklist")]
@@ -70,13 +157,213 @@ pub(crate) enum BlacklistSubcommands {
     },
 }
 
+/// The state of a setting that can be turned on or off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Toggle {
+    /// Turn the setting on.
+    On,
+
+    /// Turn the setting off.
+    Off,
+}
+
+impl Toggle {
+    /// Returns whether the setting is turned on.
+    pub fn is_on(self) -> bool {
+        self == Toggle::On
+    }
+}
+
+/// The action taken when Ctrl-C is pressed at the prompt.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum InterruptBehavior {
+    /// Discard the current input and remind the user how to exit.
+    #[default]
+    ClearLine,
+
+    /// Submit the score for the current exercise and exit, just like Ctrl-D.
+    Quit,
+
+    /// Discard the current input without printing anything.
+    Ignore,
+}
+
+/// The format of the files written by the export commands.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ExportFormat {
+    /// Comma-separated values with a header row.
+    #[default]
+    Csv,
+
+    /// A JSON array with one object per row.
+    Json,
+}
+
+/// The field of the units' search index to which a search is restricted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum SearchField {
+    /// The ID of the unit.
+    Id,
+
+    /// The name of the unit.
+    Name,
+
+    /// The description of the unit.
+    Description,
+
+    /// The metadata of the unit, indexed as `key:value` pairs.
+    Metadata,
+}
+
+impl SearchField {
+    /// Returns the name of the field in the search index.
+    pub fn index_name(self) -> &'static str {
+        match self {
+            SearchField::Id => "id",
+            SearchField::Name => "name",
+            SearchField::Description => "description",
+            SearchField::Metadata => "metadata",
+        }
+    }
+}
+
+/// The type of the units returned by a search.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum SearchUnitType {
+    /// Only return courses.
+    Course,
+
+    /// Only return lessons.
+    Lesson,
+
+    /// Only return exercises.
+    Exercise,
+}
+
+impl SearchUnitType {
+    /// Returns whether the given unit type matches this type.
+    pub fn matches(self, unit_type: &UnitType) -> bool {
+        matches!(
+            (self, unit_type),
+            (SearchUnitType::Course, UnitType::Course)
+                | (SearchUnitType::Lesson, UnitType::Lesson)
+                | (SearchUnitType::Exercise, UnitType::Exercise)
+        )
+    }
+}
+
+/// Contains subcommands for manipulating the exercise bookmarks.
+#[derive(Clone, Debug, Subcommand)]
+pub(crate) enum BookmarkSubcommands {
+    #[clap(about = "Bookmark the given exercise. \
+        The current exercise's ID is used if no ID is provided")]
+    Add {
+        #[clap(help = "The ID of the exercise")]
+        #[clap(default_value = "")]
+        exercise_id: Ustr,
+    },
+
+    #[clap(about = "Show the given bookmarked exercise for a one-off attempt")]
+    Goto {
+        #[clap(
+            help = "The position of the bookmark, as shown by the list command, or the ID of \
+            the bookmarked exercise"
+        )]
+        bookmark: String,
+
+        #[clap(
+            help = "Set a filter to only show the bookmarked exercise instead, so that it's \
+            shown by the next command and scored as part of a regular batch"
+        )]
+        #[clap(long)]
+        filter: bool,
+    },
+
+    #[clap(about = "List the bookmarked exercises")]
+    List,
+
+    #[clap(about = "Remove the given exercise from the bookmarks. \
+        The current exercise's ID is used if no ID is provided")]
+    Remove {
+        #[clap(help = "The ID of the exercise")]
+        #[clap(default_value = "")]
+        exercise_id: Ustr,
+    },
+}
+
+/// Contains subcommands for exporting the data of the library.
+#[derive(Clone, Debug, Subcommand)]
+pub(crate) enum ExportSubcommands {
+    #[clap(
+        about = "Write every trial of every exercise in the library to a file, with one row per \
+        trial containing the exercise ID, the time of the trial, and its score"
+    )]
+    Scores {
+        #[clap(help = "The path to the file")]
+        path: String,
+
+        #[clap(help = "The format of the file")]
+        #[clap(long, short, value_enum, default_value_t)]
+        format: ExportFormat,
+
+        #[clap(help = "Add the notes written about each exercise to its rows")]
+        #[clap(long)]
+        notes: bool,
+    },
+}
+
+/// Contains subcommands for showing the settings in effect.
+#[derive(Clone, Debug, Subcommand)]
+pub(crate) enum ConfigSubcommands {
+    #[clap(
+        about = "Show the open library, the active filter, the history file, the transcription \
+        download path, and the scheduler options"
+    )]
+    Show,
+}
+
 /// Contains subcommands used for debugging.
 #[derive(Clone, Debug, Subcommand)]
 pub(crate) enum DebugSubcommands {
-    #[clap(about = "Exports the dependent graph as a DOT file to the given path")]
+    #[clap(
+        about = "Adds every exercise whose asset references a missing file to the blacklist. \
+        Use the blacklist remove command to undo it"
+    )]
+    BlacklistBroken,
+
+    #[clap(
+        about = "Prints the manifest and raw assets of the given exercise for bug reports. \
+        The current exercise's ID is used if no ID is provided"
+    )]
+    DumpExercise {
+        #[clap(help = "The ID of the exercise")]
+        #[clap(default_value = "")]
+        exercise_id: Ustr,
+    },
+
+    #[clap(
+        about = "Exports the dependent graph as a DOT file to the given path. Use --around to \
+        only export the units near a course or lesson"
+    )]
     ExportGraph {
         #[clap(help = "The path to the DOT file")]
         path: String,
+
+        #[clap(long)]
+        #[clap(
+            help = "Only export the given course or lesson along with its dependencies and \
+            dependents"
+        )]
+        around: Option<Ustr>,
+
+        #[clap(long, requires = "around")]
+        #[clap(default_value = "1")]
+        #[clap(
+            help = "The maximum number of dependency edges between the exported units and the \
+            unit given with --around"
+        )]
+        depth: usize,
     },
 
     #[clap(about = "Trims the storage by removing all trials except for the most recent ones")]
@@ -86,10 +373,29 @@ pub(crate) enum DebugSubcommands {
         num_trials: usize,
     },
 
-    #[clap(about = "Prints information about the given unit")]
+    #[clap(
+        about = "Prints information about the given unit. If no ID or ? is given, the unit is \
+        picked from a list"
+    )]
+    #[clap(
+        long_about = "Prints information about the given unit. If no ID or ? is given, the unit \
+        is picked from a list.\n\n\
+        With --porcelain, the manifest is printed in a stable format meant for scripts, with one \
+        field per line as the field name and its value separated by a tab. The fields are id, \
+        type, course_id, lesson_id, name, description, exercise_type, asset_type, dependency, \
+        superseded, author, and metadata, always in that order. Fields that do not apply to the \
+        unit or are not set are omitted, and dependency, superseded, author, and metadata are \
+        repeated once per value. Metadata lines have the key and the value as two tab-separated \
+        columns. Backslashes, tabs, and newlines in values are escaped as \\\\, \\t, and \\n."
+    )]
     UnitInfo {
         #[clap(help = "The ID of the unit")]
+        #[clap(default_value = "")]
         unit_id: Ustr,
+
+        #[clap(long)]
+        #[clap(help = "Print the manifest in a stable, line-based format for scripts")]
+        porcelain: bool,
     },
 
     #[clap(about = "Prints the type of the unit with the given ID")]
@@ -98,11 +404,35 @@ pub(crate) enum DebugSubcommands {
         unit_id: Ustr,
     },
 
+    #[clap(
+        about = "Prints the given exercise through every display mode to compare how it renders. \
+        The current exercise's ID is used if no ID is provided"
+    )]
+    Render {
+        #[clap(help = "The ID of the exercise")]
+        #[clap(default_value = "")]
+        exercise_id: Ustr,
+    },
+
     #[clap(about = "Remove all the trials from units matching the given prefix")]
     RemoveScoresPrefix {
         #[clap(help = "The prefix to match against the trials")]
         prefix: String,
     },
+
+    #[clap(about = "Checks the practice stats without modifying them and prints a report")]
+    VerifyStats,
+
+    #[clap(
+        about = "Explains why the given exercise might be scheduled by showing its scores, \
+        whether it's in the blacklist or review list, and which dependencies are satisfied. \
+        The current exercise's ID is used if no ID is provided"
+    )]
+    Why {
+        #[clap(help = "The ID of the exercise")]
+        #[clap(default_value = "")]
+        exercise_id: Ustr,
+    },
 }
 
 /// Contains subcommands used for setting and displaying unit filters.
@@ -111,10 +441,26 @@ pub(crate) enum FilterSubcommands {
     #[clap(about = "Clear the unit filter if any has been set")]
     Clear,
 
-    #[clap(about = "Set the unit filter to only show exercises from the given courses")]
+    #[clap(
+        about = "Set the unit filter to only show exercises from the given courses. If no IDs \
+        are given, or an ID is ?, the course is picked from a list"
+    )]
     Courses {
         #[clap(help = "The IDs of the courses")]
         ids: Vec<Ustr>,
+
+        #[clap(
+            help = "Go through every exercise in the courses one after the other, lesson by \
+            lesson in dependency order, instead of letting the scheduler pick them"
+        )]
+        #[clap(long, alias = "all-matching")]
+        linear: bool,
+    },
+
+    #[clap(about = "Set the unit filter to only show the given exercises")]
+    Exercises {
+        #[clap(help = "The IDs of the exercises")]
+        ids: Vec<Ustr>,
     },
 
     #[clap(about = "Set the unit filter to only show exercises from the given lessons")]
@@ -142,15 +488,41 @@ pub(crate) enum FilterSubcommands {
         #[clap(name = "course-metadata")]
         #[clap(long, short)]
         #[clap(num_args = 1..)]
-        #[clap(required_unless_present("lesson-metadata"))]
+        #[clap(required_unless_present_any([
+            "lesson-metadata",
+            "exclude-course-metadata",
+            "exclude-lesson-metadata"
+        ]))]
         course_metadata: Option<Vec<KeyValue>>,
 
         #[clap(help = "Key-value pairs (written as key:value) of lesson metadata to filter on")]
         #[clap(name = "lesson-metadata")]
         #[clap(long, short)]
         #[clap(num_args = 1..)]
-        #[clap(required_unless_present("course-metadata"))]
+        #[clap(required_unless_present_any([
+            "course-metadata",
+            "exclude-course-metadata",
+            "exclude-lesson-metadata"
+        ]))]
         lesson_metadata: Option<Vec<KeyValue>>,
+
+        #[clap(
+            help = "Key-value pairs (written as key:value) of course metadata to exclude. \
+            Courses with any of the pairs are left out"
+        )]
+        #[clap(name = "exclude-course-metadata")]
+        #[clap(long)]
+        #[clap(num_args = 1..)]
+        exclude_course_metadata: Option<Vec<KeyValue>>,
+
+        #[clap(
+            help = "Key-value pairs (written as key:value) of lesson metadata to exclude. \
+            Lessons with any of the pairs are left out"
+        )]
+        #[clap(name = "exclude-lesson-metadata")]
+        #[clap(long)]
+        #[clap(num_args = 1..)]
+        exclude_lesson_metadata: Option<Vec<KeyValue>>,
     },
 
     #[clap(about = "Set the unit filter to only show exercises from the units in the review list")]
@@ -162,6 +534,10 @@ pub(crate) enum FilterSubcommands {
     Dependents {
         #[clap(help = "The IDs of the units")]
         ids: Vec<Ustr>,
+
+        #[clap(help = "The maximum depth to search for dependents. There's no limit if omitted")]
+        #[clap(long, short)]
+        depth: Option<usize>,
     },
 
     #[clap(
@@ -176,6 +552,18 @@ pub(crate) enum FilterSubcommands {
         depth: usize,
     },
 
+    #[clap(
+        about = "Save the current unit filter so that it can be selected later with filter set"
+    )]
+    Save {
+        #[clap(help = "The ID of the saved filter")]
+        id: String,
+
+        #[clap(help = "A description of the saved filter")]
+        #[clap(required = true)]
+        description: Vec<String>,
+    },
+
     #[clap(about = "Select the saved filter with the given ID")]
     Set {
         #[clap(help = "The ID of the saved filter")]
@@ -210,30 +598,91 @@ pub(crate) enum InstructionSubcommands {
 #[derive(Clone, Debug, Subcommand)]
 pub(crate) enum ListSubcommands {
     #[clap(about = "Show the IDs of all courses in the library")]
-    Courses,
+    Courses {
+        #[clap(help = "Show a badge with the aggregate mastery of each unit")]
+        #[clap(long)]
+        mastery: bool,
+
+        #[clap(
+            help = "Show how many of the exercises in each unit have been scored and their \
+            average score"
+        )]
+        #[clap(long, conflicts_with = "mastery")]
+        progress: bool,
+
+        #[clap(help = "Only show the number of units")]
+        #[clap(long, conflicts_with_all = ["mastery", "progress"])]
+        count: bool,
+    },
 
     #[clap(about = "Show the dependencies of the given unit")]
     Dependencies {
         #[clap(help = "The ID of the unit")]
         unit_id: Ustr,
+
+        #[clap(help = "Show a badge with the aggregate mastery of each unit")]
+        #[clap(long)]
+        mastery: bool,
+
+        #[clap(help = "Only show the number of units")]
+        #[clap(long, conflicts_with = "mastery")]
+        count: bool,
     },
 
     #[clap(about = "Show the dependents of the given unit")]
     Dependents {
         #[clap(help = "The ID of the unit")]
         unit_id: Ustr,
+
+        #[clap(help = "Show a badge with the aggregate mastery of each unit")]
+        #[clap(long)]
+        mastery: bool,
+
+        #[clap(help = "Only show the number of units")]
+        #[clap(long, conflicts_with = "mastery")]
+        count: bool,
     },
 
     #[clap(about = "Show the IDs of all exercises in the given lesson")]
     Exercises {
         #[clap(help = "The ID of the lesson")]
         lesson_id: Ustr,
+
+        #[clap(help = "Show a badge with the aggregate mastery of each unit")]
+        #[clap(long)]
+        mastery: bool,
+
+        #[clap(help = "Only show the number of units")]
+        #[clap(long, conflicts_with = "mastery")]
+        count: bool,
+
+        #[clap(
+            help = "Show the most recent score of each exercise, listing the exercises that were \
+            never scored first"
+        )]
+        #[clap(long, conflicts_with_all = ["mastery", "count"])]
+        with_scores: bool,
     },
 
     #[clap(about = "Show the IDs of all lessons in the given course")]
     Lessons {
         #[clap(help = "The ID of the course")]
         course_id: Ustr,
+
+        #[clap(help = "Show a badge with the aggregate mastery of each unit")]
+        #[clap(long)]
+        mastery: bool,
+
+        #[clap(
+            help = "Show how many of the exercises in each unit have been scored and their \
+            average score"
+        )]
+        #[clap(long, conflicts_with = "mastery")]
+        progress: bool,
+
+        #[clap(help = "Only show the number of units")]
+        #[clap(long, conflicts_with_all = ["mastery", "progress"])]
+        count: bool,
     },
 
     #[clap(about = "Show the IDs of all the lessons in the given course \
@@ -267,6 +716,38 @@ pub(crate) enum MaterialSubcommands {
     },
 }
 
+/// Contains subcommands used for writing notes about the current exercise.
+#[derive(Clone, Debug, Subcommand)]
+pub(crate) enum NoteSubcommands {
+    #[clap(about = "Add a note to the current exercise")]
+    Add {
+        #[clap(help = "The text of the note")]
+        text: Vec<String>,
+    },
+
+    #[clap(about = "Remove all the notes from the current exercise")]
+    Clear,
+
+    #[clap(about = "Show the notes for the current exercise")]
+    Show,
+}
+
+/// Contains subcommands used for practicing a whole course or lesson right away.
+#[derive(Clone, Debug, Subcommand)]
+pub(crate) enum PracticeSubcommands {
+    #[clap(about = "Only show exercises from the given course and show the first one")]
+    Course {
+        #[clap(help = "The ID of the course")]
+        course_id: Ustr,
+    },
+
+    #[clap(about = "Only show exercises from the given lesson and show the first one")]
+    Lesson {
+        #[clap(help = "The ID of the lesson")]
+        lesson_id: Ustr,
+    },
+}
+
 /// Contains subcommands used for manipulating git repositories containing Trane courses.
 #[derive(Clone, Debug, Subcommand)]
 pub(crate) enum RepositorySubcommands {
@@ -281,6 +762,13 @@ pub(crate) enum RepositorySubcommands {
         )]
         #[clap(long, short)]
         repo_id: Option<String>,
+
+        #[clap(
+            help = "The branch, tag, or commit to check out instead of the default branch. \
+            The repository stays pinned to it when it's updated"
+        )]
+        #[clap(long = "ref", alias = "branch", value_name = "REF")]
+        git_ref: Option<String>,
     },
 
     #[clap(about = "Remove the git repository with the given ID from the library")]
@@ -290,7 +778,13 @@ pub(crate) enum RepositorySubcommands {
     },
 
     #[clap(about = "List the managed git repositories in the library")]
-    List,
+    List {
+        #[clap(
+            help = "Also show whether each repository is up to date and when it was last updated"
+        )]
+        #[clap(long, short)]
+        verbose: bool,
+    },
 
     #[clap(about = "Update the managed git repository with the given ID")]
     Update {
@@ -299,25 +793,52 @@ pub(crate) enum RepositorySubcommands {
     },
 
     #[clap(about = "Update all the managed git repositories in the library")]
-    UpdateAll,
+    UpdateAll {
+        #[clap(
+            help = "Fetch each repository and report whether an update is available instead of \
+            updating it"
+        )]
+        #[clap(long)]
+        dry_run: bool,
+    },
 }
 
 /// Contains subcommands used for manipulating the review list.
 #[derive(Clone, Debug, Subcommand)]
 pub(crate) enum ReviewListSubcommands {
-    #[clap(about = "Add the given unit to the review list")]
+    #[clap(
+        about = "Add the given units to the review list. The batch is reset once after all \
+        the units are added"
+    )]
     Add {
-        #[clap(help = "The ID of the unit")]
-        unit_id: Ustr,
+        #[clap(help = "The IDs of the units")]
+        #[clap(required = true)]
+        unit_ids: Vec<Ustr>,
+    },
+
+    #[clap(about = "Write the units in the review list to a file, one per line")]
+    Export {
+        #[clap(help = "The path to the file")]
+        path: String,
+    },
+
+    #[clap(about = "Add the units listed in a file, one per line, to the review list")]
+    Import {
+        #[clap(help = "The path to the file")]
+        path: String,
     },
 
     #[clap(about = "List all the units in the review list")]
     List,
 
-    #[clap(about = "Remove the given unit from the review list")]
+    #[clap(
+        about = "Remove the given units from the review list. The batch is reset once after \
+        all the units are removed"
+    )]
     Remove {
-        #[clap(help = "The ID of the unit")]
-        unit_id: Ustr,
+        #[clap(help = "The IDs of the units")]
+        #[clap(required = true)]
+        unit_ids: Vec<Ustr>,
     },
 }
 
@@ -326,17 +847,78 @@ pub(crate) enum SchedulerOptionsSubcommands {
     #[clap(about = "Reset the scheduler options to their default values")]
     Reset,
 
-    #[clap(about = "Set the scheduler options to the given values")]
+    #[clap(about = "Set the given scheduler options. Options not given keep their current value")]
     Set {
         #[clap(help = "The new batch size")]
         #[clap(long, short)]
-        batch_size: usize,
+        batch_size: Option<usize>,
+
+        #[clap(help = "The fraction (0.0-1.0) of each batch taken from new exercises")]
+        #[clap(long)]
+        new_percentage: Option<f32>,
+
+        #[clap(help = "The fraction (0.0-1.0) of each batch taken from the target window")]
+        #[clap(long)]
+        target_percentage: Option<f32>,
+
+        #[clap(help = "The fraction (0.0-1.0) of each batch taken from the current window")]
+        #[clap(long)]
+        current_percentage: Option<f32>,
+
+        #[clap(help = "The fraction (0.0-1.0) of each batch taken from the easy window")]
+        #[clap(long)]
+        easy_percentage: Option<f32>,
+
+        #[clap(help = "The fraction (0.0-1.0) of each batch taken from the mastered window")]
+        #[clap(long)]
+        mastered_percentage: Option<f32>,
+
+        #[clap(help = "The constant score required to move on to the dependents of a unit")]
+        #[clap(long)]
+        passing_score: Option<f32>,
+
+        #[clap(help = "The minimum score required for a unit to supersede another")]
+        #[clap(long)]
+        superseding_score: Option<f32>,
+
+        #[clap(help = "The number of trials used to compute the score of an exercise")]
+        #[clap(long)]
+        num_trials: Option<usize>,
     },
 
     #[clap(about = "Show the current scheduler options")]
     Show,
 }
 
+/// Contains subcommands used for showing information about the current session.
+#[derive(Clone, Debug, Subcommand)]
+pub(crate) enum SessionSubcommands {
+    #[clap(about = "Show the number of exercises scored in this session and their scores")]
+    Stats,
+}
+
+/// Contains subcommands used for showing statistics about the user's practice.
+#[derive(Clone, Debug, Subcommand)]
+pub(crate) enum StatsSubcommands {
+    #[clap(
+        about = "Estimate how many exercises will need to be reviewed on each of the next days"
+    )]
+    Forecast {
+        #[clap(help = "The number of days to forecast")]
+        #[clap(default_value = "14")]
+        days: usize,
+    },
+    #[clap(
+        about = "Show the number of exercises, their average score, and how many are mastered \
+        for each course in the library"
+    )]
+    Overview {
+        #[clap(help = "The minimum score (1-5) for an exercise to count as mastered")]
+        #[clap(long, short, default_value = "4.0")]
+        threshold: f32,
+    },
+}
+
 /// Contains subcommands used for setting and displaying study sessions.
 #[derive(Clone, Debug, Subcommand)]
 pub(crate) enum StudySessionSubcommands {
@@ -356,6 +938,56 @@ pub(crate) enum StudySessionSubcommands {
     Show,
 }
 
+/// Contains subcommands used for temporarily suspending units.
+#[derive(Clone, Debug, Subcommand)]
+pub(crate) enum SuspendSubcommands {
+    #[clap(
+        about = "Suspend the given unit so that its exercises are not shown for the given number \
+        of days"
+    )]
+    Add {
+        #[clap(help = "The ID of the unit")]
+        unit_id: Ustr,
+
+        #[clap(help = "The number of days for which the unit is suspended")]
+        days: u32,
+    },
+
+    #[clap(about = "List the suspended units and the time left until their suspensions expire")]
+    List,
+
+    #[clap(about = "Lift the suspension of the given unit before it expires")]
+    Remove {
+        #[clap(help = "The ID of the unit")]
+        unit_id: Ustr,
+    },
+}
+
+/// Contains subcommands used for managing the instruments used in transcription courses.
+#[derive(Clone, Debug, Subcommand)]
+pub(crate) enum InstrumentSubcommands {
+    #[clap(about = "Add an instrument to the list of instruments for transcription courses")]
+    Add {
+        #[clap(
+            help = "The ID of the instrument (e.g., tenor_saxophone). It's used to generate \
+            the IDs of the lessons, so changing it later will lose your progress"
+        )]
+        id: String,
+
+        #[clap(help = "The name of the instrument (e.g., Tenor Saxophone)")]
+        name: Vec<String>,
+    },
+
+    #[clap(about = "List the instruments for transcription courses")]
+    List,
+
+    #[clap(about = "Remove an instrument from the list of instruments for transcription courses")]
+    Remove {
+        #[clap(help = "The ID of the instrument")]
+        id: String,
+    },
+}
+
 /// Contains subcommands used for dealing with transcription exercises.
 #[derive(Clone, Debug, Subcommand)]
 pub(crate) enum TranscriptionSubcommands {
@@ -364,14 +996,27 @@ pub(crate) enum TranscriptionSubcommands {
     Download {
         #[clap(help = "The ID of the exercise")]
         #[clap(default_value = "")]
+        #[clap(conflicts_with_all = ["course", "lesson"])]
         exercise_id: Ustr,
 
+        #[clap(help = "Download the assets of all the transcription exercises in the course")]
+        #[clap(long, conflicts_with = "lesson")]
+        course: Option<Ustr>,
+
+        #[clap(help = "Download the assets of all the transcription exercises in the lesson")]
+        #[clap(long)]
+        lesson: Option<Ustr>,
+
         #[clap(help = "Whether to redownload the asset if it already exists")]
         #[clap(default_value = "false")]
         #[clap(long, short)]
         redownload: bool,
     },
 
+    #[clap(about = "Subcommands for managing the instruments used in transcription courses")]
+    #[clap(subcommand)]
+    Instruments(InstrumentSubcommands),
+
     #[clap(
         about = "Checks if the the asset for the given transcription exercise has been \
         downloaded. The current exercise's ID is used if no ID is provided"
@@ -391,33 +1036,162 @@ pub(crate) enum TranscriptionSubcommands {
         #[clap(default_value = "")]
         exercise_id: Ustr,
     },
+    #[clap(
+        about = "Opens the downloaded asset for the given transcription exercise in a media \
+        player. The current exercise's ID is used if no ID is provided"
+    )]
+    Play {
+        #[clap(help = "The ID of the exercise")]
+        #[clap(default_value = "")]
+        exercise_id: Ustr,
+
+        #[clap(help = "Download the asset first if it has not been downloaded")]
+        #[clap(long, short)]
+        download: bool,
+
+        #[clap(
+            help = "The command used to play the asset, which is passed the path to the file. \
+            The default application for the file is used if not given"
+        )]
+        #[clap(long, short)]
+        player: Option<String>,
+    },
 }
 
-/// Contains the available subcommands.
+/// Contains the available subcommands. The commands used most often while practicing have
+/// one-letter aliases: `a` for `answer`, `c` for `current`, `n` for `next`, and `s` for `score`.
 #[derive(Clone, Debug, Subcommand)]
 pub(crate) enum Subcommands {
     #[clap(about = "Show the answer to the current exercise, if it exists")]
-    Answer,
+    #[clap(visible_alias = "a")]
+    Answer {
+        #[clap(long)]
+        #[clap(
+            help = "The number of seconds to wait before showing the answer. Overrides the delay \
+            set with the reveal-delay command"
+        )]
+        reveal_delay: Option<u64>,
+    },
+
+    #[clap(
+        about = "Turn showing the answer right after scoring an exercise with the score or next \
+        commands on or off. Only flashcards have answers, so other exercises are not affected"
+    )]
+    AutoAnswer {
+        #[clap(help = "Whether to show the answer after scoring")]
+        state: Toggle,
+    },
+
+    #[clap(
+        about = "Turn showing the first exercise of a new batch whenever a command resets the \
+        batch on or off. Commands that reset the batch include changing the filter or study \
+        session and editing the blacklist or review list"
+    )]
+    AutoNext {
+        #[clap(help = "Whether to show a new exercise after the batch is reset")]
+        state: Toggle,
+    },
+
+    #[clap(
+        about = "Set how many of the most recently shown exercises are skipped by the next \
+        command to avoid immediate repeats. Call without arguments to show the current value"
+    )]
+    AvoidRepeats {
+        #[clap(help = "The number of recent exercises to skip. Set to 0 to disable")]
+        count: Option<usize>,
+    },
 
     #[clap(about = "Subcommands to manipulate the unit blacklist")]
     #[clap(subcommand)]
     Blacklist(BlacklistSubcommands),
 
+    #[clap(about = "Subcommands to manipulate the exercise bookmarks")]
+    #[clap(subcommand, visible_alias = "mark")]
+    Bookmark(BookmarkSubcommands),
+
+    #[clap(about = "Subcommands for showing the settings in effect")]
+    #[clap(subcommand)]
+    Config(ConfigSubcommands),
+
     #[clap(about = "Display the current exercise")]
-    Current,
+    #[clap(visible_alias = "c")]
+    Current {
+        #[clap(help = "Print only the ID of the current exercise, without any decoration")]
+        #[clap(long)]
+        id_only: bool,
+
+        #[clap(
+            help = "Copy the ID of the current exercise to the clipboard using pbcopy, wl-copy, \
+            xclip, xsel, or clip.exe"
+        )]
+        #[clap(long)]
+        copy: bool,
+    },
 
     #[clap(about = "Subcommands for debugging purposes")]
     #[clap(subcommand)]
     Debug(DebugSubcommands),
 
+    #[clap(about = "Subcommands for exporting the data of the library")]
+    #[clap(subcommand)]
+    Export(ExportSubcommands),
+
     #[clap(about = "Subcommands for dealing with unit filters")]
     #[clap(subcommand)]
     Filter(FilterSubcommands),
 
+    #[clap(
+        about = "Turn showing flashcards back to front on or off. When on, the back of each \
+        flashcard is shown as the exercise and the front as the answer. Flashcards without a back \
+        are shown as usual"
+    )]
+    #[clap(visible_alias = "reverse")]
+    Flip {
+        #[clap(help = "Whether to show flashcards back to front")]
+        state: Toggle,
+    },
+
+    #[clap(about = "Turn focus mode on or off. \
+        In focus mode, exercises are shown without their IDs")]
+    Focus {
+        #[clap(help = "Whether to turn focus mode on or off")]
+        state: Toggle,
+    },
+
+    #[clap(about = "Go to the exercise at the given position in the current batch")]
+    Goto {
+        #[clap(help = "The position of the exercise in the batch, starting at one")]
+        position: usize,
+    },
+
+    #[clap(
+        about = "Enter grade mode to score exercises with single key presses. \
+        Press space to show the answer, 1-5 to score and move on, and Esc to exit"
+    )]
+    Grade,
+
+    #[clap(
+        about = "Show the instructions of the current exercise's lesson, or those of its course if \
+        the lesson has none"
+    )]
+    Hint,
+
     #[clap(about = "Subcommands for showing course and lesson instructions")]
     #[clap(subcommand)]
     Instructions(InstructionSubcommands),
 
+    #[clap(about = "Set what happens when Ctrl-C is pressed at the prompt")]
+    Interrupt {
+        #[clap(help = "The action to take on Ctrl-C")]
+        behavior: InterruptBehavior,
+    },
+
+    #[clap(
+        about = "Show the total number of trials and exercises practiced since the first \
+        practice"
+    )]
+    Lifetime,
+
     #[clap(about = "Subcommands for listing course, lesson, and exercise IDs")]
     #[clap(subcommand)]
     List(ListSubcommands),
@@ -429,16 +1203,32 @@ pub(crate) enum Subcommands {
     #[clap(
         long_about = "Trane \"recites\" Tara Sarasvati's mantra in the background as a symbolic \
             way in which users can contribute back to the Trane Project. This command shows the \
-            number of mantras that Trane has recited so far."
+            number of mantras that Trane has recited so far. The count of each session is added \
+            to a running total stored in the library when the session ends."
     )]
-    MantraCount,
+    MantraCount {
+        #[clap(long)]
+        #[clap(help = "Show the total across all the sessions with the open library")]
+        lifetime: bool,
+    },
 
     #[clap(about = "Subcommands for showing course and lesson materials")]
     #[clap(subcommand)]
     Material(MaterialSubcommands),
 
     #[clap(about = "Submits the score for the current exercise and proceeds to the next")]
-    Next,
+    #[clap(visible_alias = "n")]
+    Next {
+        #[clap(
+            help = "The mastery score to record for the current exercise before proceeding, \
+            either 1-5 or one of bad, poor, ok, good, or great"
+        )]
+        score: Option<ScoreArg>,
+    },
+
+    #[clap(about = "Subcommands for writing notes about the current exercise")]
+    #[clap(subcommand)]
+    Note(NoteSubcommands),
 
     #[clap(about = "Open the course library at the given location")]
     Open {
@@ -446,8 +1236,59 @@ pub(crate) enum Subcommands {
         library_path: String,
     },
 
-    #[clap(about = "Quit Trane")]
-    Quit,
+    #[clap(about = "Open the SoundSlice link of the current exercise in the default browser")]
+    OpenAsset,
+
+    #[clap(
+        about = "Show the given exercise right away so that it can be practiced and scored. \
+        The next command continues with the current batch. Use the course or lesson subcommands \
+        to practice a whole unit instead"
+    )]
+    #[clap(args_conflicts_with_subcommands = true)]
+    #[clap(subcommand_negates_reqs = true)]
+    Practice {
+        #[clap(help = "The ID of the exercise")]
+        #[clap(required = true)]
+        exercise_id: Option<Ustr>,
+
+        #[clap(subcommand)]
+        unit: Option<PracticeSubcommands>,
+    },
+
+    #[clap(about = "Go back to the previous exercise in the current batch")]
+    Prev,
+
+    #[clap(
+        about = "Show the exercises the scheduler would select with the current filter \
+        without changing the current batch"
+    )]
+    Preview {
+        #[clap(help = "The maximum number of exercises to show")]
+        count: Option<usize>,
+    },
+
+    #[clap(about = "Turn showing the course ID of the current exercise in the prompt on or off")]
+    Prompt {
+        #[clap(help = "Whether to show the course ID in the prompt")]
+        state: Toggle,
+    },
+
+    #[clap(about = "Quit Trane. The pending score for the current exercise is submitted first")]
+    Quit {
+        #[clap(help = "Drop the pending score instead of submitting it")]
+        #[clap(long)]
+        discard: bool,
+    },
+
+    #[clap(
+        about = "Estimate how many successful reviews are left until all the exercises \
+        in the current course or lesson filter reach the target score"
+    )]
+    Remaining {
+        #[clap(help = "The target score (1-5) for every exercise")]
+        #[clap(long, short, default_value = "4.0")]
+        target: f32,
+    },
 
     #[clap(about = "Subcommands for manipulating git repositories containing Trane courses")]
     #[clap(subcommand)]
@@ -456,20 +1297,50 @@ pub(crate) enum Subcommands {
     #[clap(about = "Resets the current exercise batch")]
     ResetBatch,
 
+    #[clap(
+        about = "Set how many seconds the answer command counts down before showing the answer, \
+        to encourage attempting the exercise first. Call without arguments to show the current \
+        value"
+    )]
+    RevealDelay {
+        #[clap(help = "The number of seconds to wait. Set to 0 to show the answer right away")]
+        seconds: Option<u64>,
+    },
+
     #[clap(about = "Subcommands for manipulating the review list")]
     #[clap(subcommand)]
     ReviewList(ReviewListSubcommands),
 
     #[clap(about = "Record the mastery score (1-5) for the current exercise")]
+    #[clap(visible_alias = "s")]
     Score {
-        #[clap(help = "The mastery score (1-5) for the current exercise")]
-        score: u8,
+        #[clap(
+            help = "The mastery score for the current exercise, either 1-5 or one of bad, poor, \
+            ok, good, or great"
+        )]
+        score: ScoreArg,
+
+        #[clap(long, num_args = 1..)]
+        #[clap(help = "A note saved with the score, shown by the scores command")]
+        note: Vec<String>,
     },
 
     #[clap(about = "Search for courses, lessons, and exercises")]
     Search {
         #[clap(help = "The search query")]
         terms: Vec<String>,
+
+        #[clap(help = "Only search the given field of the units")]
+        #[clap(long, short)]
+        field: Option<SearchField>,
+
+        #[clap(help = "Only show units of the given type")]
+        #[clap(long = "type", short = 't')]
+        unit_type: Option<SearchUnitType>,
+
+        #[clap(help = "The maximum number of results to show")]
+        #[clap(long, short)]
+        limit: Option<usize>,
     },
 
     #[clap(about = "Show the most recent scores for the given exercise")]
@@ -481,19 +1352,90 @@ pub(crate) enum Subcommands {
         #[clap(help = "The number of scores to show")]
         #[clap(long, short, default_value = "20")]
         num_scores: usize,
+
+        #[clap(help = "Show the scores as a sparkline instead of a table")]
+        #[clap(long, short)]
+        graph: bool,
     },
 
     #[clap(about = "Subcommands for manipulating the exercise scheduler")]
     #[clap(subcommand)]
     SchedulerOptions(SchedulerOptionsSubcommands),
 
+    #[clap(about = "Subcommands for showing information about the current session")]
+    #[clap(subcommand)]
+    Session(SessionSubcommands),
+
+    #[clap(about = "Subcommands for showing statistics about your practice")]
+    #[clap(subcommand)]
+    Stats(StatsSubcommands),
+
+    #[clap(
+        about = "Show a summary of the open library, the current filter and batch, and \
+        today's practice"
+    )]
+    Status,
+
     #[clap(about = "Subcommands for setting and displaying study sessions")]
     #[clap(subcommand)]
     StudySession(StudySessionSubcommands),
 
+    #[clap(
+        about = "Subcommands for suspending units for a few days. Unlike the blacklist, \
+        suspensions expire on their own"
+    )]
+    #[clap(subcommand)]
+    Suspend(SuspendSubcommands),
+
     #[clap(about = "Subcommands for dealing with transcription exercises")]
     #[clap(subcommand)]
     Transcription(TranscriptionSubcommands),
+
+    #[clap(about = "Remove the score submitted most recently in this session")]
+    Undo,
+
+    #[clap(about = "Show the versions of Trane, the CLI, and yt-dlp")]
+    Version,
+}
+
+/// The options passed to the binary at startup. Commands are entered at the prompt afterwards.
+#[derive(Debug, Parser)]
+#[clap(name = "trane")]
+#[clap(author, version, long_about = None)]
+#[clap(about = "A command-line interface for Trane. Run without arguments to start the prompt")]
+#[allow(clippy::struct_excessive_bools)]
+pub(crate) struct TraneArgs {
+    #[clap(help = "The path to a course library to open before starting the prompt")]
+    pub library_path: Option<String>,
+
+    #[clap(
+        help = "Execute the commands in the given file, one per line, and exit \
+        instead of starting the prompt. The exit code is 1 if a command failed, 3 if the \
+        script or the library could not be opened, and 4 if a command printed a warning \
+        in strict mode"
+    )]
+    #[clap(long)]
+    pub script: Option<String>,
+
+    #[clap(help = "Keep executing the script after a command fails")]
+    #[clap(long, requires = "script")]
+    pub continue_on_error: bool,
+
+    #[clap(help = "Treat commands in the script that print a warning as failed")]
+    #[clap(long, requires = "script")]
+    pub strict: bool,
+
+    #[clap(
+        help = "Print long instructions, material, and lists without paging them. \
+        Paging can also be disabled by setting the TRANE_NO_PAGER environment variable"
+    )]
+    #[clap(long)]
+    pub no_pager: bool,
+
+    #[clap(help = "Reject all the commands that modify the library or its data, \
+        including submitting scores")]
+    #[clap(long)]
+    pub read_only: bool,
 }
 
 /// A command-line interface for Trane.
@@ -503,21 +1445,119 @@ pub(crate) enum Subcommands {
 pub(crate) struct TraneCli {
     #[clap(subcommand)]
     pub commands: Subcommands,
+
+    #[clap(help = "Print how long the command took to execute to stderr")]
+    #[clap(long, global = true)]
+    pub time: bool,
+
+    #[clap(
+        help = "Print the results of list commands, preview, blacklist, review list, and scores \
+        as JSON"
+    )]
+    #[clap(long, global = true)]
+    pub json: bool,
+}
+
+/// The maximum edit distance between an unknown command and a known one for the latter to be
+/// suggested.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Returns the number of single-character insertions, deletions, or substitutions needed to turn
+/// one string into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
 }
 
 impl TraneCli {
+    /// Returns the name of the top-level command closest to the given unknown name, if there's one
+    /// close enough to be a likely typo. Returns `None` if the name is already a valid command or
+    /// an alias of one. Aliases are not suggested, only the full names.
+    pub fn suggest_command(name: &str) -> Option<String> {
+        let command = Self::command();
+        if command.find_subcommand(name).is_some() {
+            return None;
+        }
+        command
+            .get_subcommands()
+            .map(Command::get_name)
+            .map(|candidate| (edit_distance(name, candidate), candidate))
+            .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+            .min()
+            .map(|(_, candidate)| candidate.to_string())
+    }
+
     /// Executes the parsed subcommand. Returns true if the application should continue running.
+    /// If auto-next is on and the command reset the batch, the first exercise of a new batch is
+    /// shown afterwards.
     pub fn execute_subcommand(&self, app: &mut TraneApp) -> Result<bool> {
+        app.set_output_format(if self.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Text
+        });
+        app.clear_batch_reset();
+        let result = self.execute_command(app);
+        if let Ok(true) = result {
+            app.show_next_after_reset()?;
+        }
+        result
+    }
+
+    /// Executes the parsed command without any of the follow-up actions.
+    fn execute_command(&self, app: &mut TraneApp) -> Result<bool> {
         match self.commands.clone() {
-            Subcommands::Answer => {
-                app.show_answer()?;
+            Subcommands::Answer { reveal_delay } => {
+                app.reveal_answer(reveal_delay)?;
+                Ok(true)
+            }
+
+            Subcommands::AutoAnswer { state } => {
+                app.set_auto_answer(state.is_on());
+                if state.is_on() {
+                    println!("The answer will be shown after scoring an exercise");
+                } else {
+                    println!("The answer command must be used to show the answer");
+                }
+                Ok(true)
+            }
+
+            Subcommands::AutoNext { state } => {
+                app.set_auto_next(state.is_on());
+                if state.is_on() {
+                    println!("A new exercise will be shown whenever the batch is reset");
+                } else {
+                    println!("The next command must be used after the batch is reset");
+                }
+                Ok(true)
+            }
+
+            Subcommands::AvoidRepeats { count } => {
+                if let Some(count) = count {
+                    app.set_recent_exercises(count);
+                }
+                match app.recent_exercises() {
+                    0 => println!("Skipping recent exercises is disabled"),
+                    count => println!("The next command skips the last {count} exercises shown"),
+                }
                 Ok(true)
             }
 
             Subcommands::Blacklist(subcommand) => match subcommand {
-                BlacklistSubcommands::Add { unit_id } => {
-                    app.blacklist_unit(unit_id)?;
-                    println!("Added unit {unit_id} to the blacklist");
+                BlacklistSubcommands::Add { unit_ids } => {
+                    app.blacklist_units(&unit_ids)?;
+                    for unit_id in unit_ids {
+                        println!("Added unit {unit_id} to the blacklist");
+                    }
                     Ok(true)
                 }
                 BlacklistSubcommands::Course => {
@@ -530,14 +1570,26 @@ impl TraneCli {
                     println!("Added current exercise to the blacklist");
                     Ok(true)
                 }
+                BlacklistSubcommands::Export { path } => {
+                    let num_entries = app.export_blacklist(Path::new(&path))?;
+                    println!("Exported {num_entries} blacklist entries to {path}");
+                    Ok(true)
+                }
+                BlacklistSubcommands::Import { path } => {
+                    let num_entries = app.import_blacklist(Path::new(&path))?;
+                    println!("Imported {num_entries} blacklist entries from {path}");
+                    Ok(true)
+                }
                 BlacklistSubcommands::Lesson => {
                     app.blacklist_lesson()?;
                     println!("Added current exercise's lesson to the blacklist");
                     Ok(true)
                 }
-                BlacklistSubcommands::Remove { unit_id } => {
-                    app.remove_from_blacklist(unit_id)?;
-                    println!("Removed {unit_id} from the blacklist");
+                BlacklistSubcommands::Remove { unit_ids } => {
+                    app.remove_from_blacklist(&unit_ids)?;
+                    for unit_id in unit_ids {
+                        println!("Removed {unit_id} from the blacklist");
+                    }
                     Ok(true)
                 }
                 BlacklistSubcommands::RemovePrefix { prefix } => {
@@ -551,14 +1603,69 @@ impl TraneCli {
                 }
             },
 
-            Subcommands::Current => {
-                app.current()?;
+            Subcommands::Bookmark(subcommand) => match subcommand {
+                BookmarkSubcommands::Add { exercise_id } => {
+                    let exercise_id = app.add_bookmark(exercise_id)?;
+                    println!("Added exercise {exercise_id} to the bookmarks");
+                    Ok(true)
+                }
+                BookmarkSubcommands::Goto { bookmark, filter } => {
+                    let exercise_id = app.goto_bookmark(&bookmark, filter)?;
+                    if filter {
+                        println!("Set the unit filter to only show exercise {exercise_id}");
+                    }
+                    Ok(true)
+                }
+                BookmarkSubcommands::List => {
+                    app.list_bookmarks()?;
+                    Ok(true)
+                }
+                BookmarkSubcommands::Remove { exercise_id } => {
+                    let exercise_id = app.remove_bookmark(exercise_id)?;
+                    println!("Removed exercise {exercise_id} from the bookmarks");
+                    Ok(true)
+                }
+            },
+
+            Subcommands::Config(subcommand) => match subcommand {
+                ConfigSubcommands::Show => {
+                    app.show_config()?;
+                    Ok(true)
+                }
+            },
+
+            Subcommands::Current { id_only, copy } => {
+                if id_only {
+                    app.current_id()?;
+                } else {
+                    app.current()?;
+                }
+                if copy {
+                    app.copy_current_id()?;
+
+                    // Keep the output undecorated when only the ID is printed.
+                    if !id_only {
+                        println!("Copied the exercise ID to the clipboard");
+                    }
+                }
                 Ok(true)
             }
 
             Subcommands::Debug(subcommand) => match subcommand {
-                DebugSubcommands::ExportGraph { path } => {
-                    app.export_graph(Path::new(&path))?;
+                DebugSubcommands::BlacklistBroken => {
+                    app.blacklist_broken_exercises()?;
+                    Ok(true)
+                }
+                DebugSubcommands::DumpExercise { exercise_id } => {
+                    app.dump_exercise(exercise_id)?;
+                    Ok(true)
+                }
+                DebugSubcommands::ExportGraph {
+                    path,
+                    around,
+                    depth,
+                } => {
+                    app.export_graph(Path::new(&path), around, depth)?;
                     println!("Exported graph to {path}");
                     Ok(true)
                 }
@@ -566,8 +1673,8 @@ impl TraneCli {
                     app.trim_scores(num_trials)?;
                     Ok(true)
                 }
-                DebugSubcommands::UnitInfo { unit_id } => {
-                    app.show_unit_info(unit_id)?;
+                DebugSubcommands::UnitInfo { unit_id, porcelain } => {
+                    app.show_unit_info(unit_id, porcelain)?;
                     Ok(true)
                 }
                 DebugSubcommands::UnitType { unit_id } => {
@@ -575,10 +1682,34 @@ impl TraneCli {
                     println!("The type of the unit with ID {unit_id} is {unit_type:?}");
                     Ok(true)
                 }
+                DebugSubcommands::Render { exercise_id } => {
+                    app.render_exercise(exercise_id)?;
+                    Ok(true)
+                }
                 DebugSubcommands::RemoveScoresPrefix { prefix } => {
                     app.remove_prefix_from_scores(&prefix)?;
                     Ok(true)
                 }
+                DebugSubcommands::VerifyStats => {
+                    app.verify_stats()?;
+                    Ok(true)
+                }
+                DebugSubcommands::Why { exercise_id } => {
+                    app.explain_exercise(exercise_id)?;
+                    Ok(true)
+                }
+            },
+
+            Subcommands::Export(subcommand) => match subcommand {
+                ExportSubcommands::Scores {
+                    path,
+                    format,
+                    notes,
+                } => {
+                    let num_trials = app.export_scores(Path::new(&path), format, notes)?;
+                    println!("Exported {num_trials} trials to {path}");
+                    Ok(true)
+                }
             },
 
             Subcommands::Filter(subcommand) => match subcommand {
@@ -587,9 +1718,23 @@ impl TraneCli {
                     println!("Cleared the unit filter");
                     Ok(true)
                 }
-                FilterSubcommands::Courses { ids } => {
-                    app.filter_courses(&ids)?;
-                    println!("Set the unit filter to only show exercises from the given courses");
+                FilterSubcommands::Courses { ids, linear } => {
+                    app.filter_courses(&ids, linear)?;
+                    if linear {
+                        println!(
+                            "Set the unit filter to show every exercise from the given courses \
+                            in order"
+                        );
+                    } else {
+                        println!(
+                            "Set the unit filter to only show exercises from the given courses"
+                        );
+                    }
+                    Ok(true)
+                }
+                FilterSubcommands::Exercises { ids } => {
+                    app.filter_exercises(&ids)?;
+                    println!("Set the unit filter to only show the given exercises");
                     Ok(true)
                 }
                 FilterSubcommands::Lessons { ids } => {
@@ -606,12 +1751,20 @@ impl TraneCli {
                     any,
                     lesson_metadata,
                     course_metadata,
+                    exclude_lesson_metadata,
+                    exclude_course_metadata,
                 } => {
                     let filter_op = match (any, all) {
                         (true, _) => FilterOp::Any,
                         (false, false) | (_, true) => FilterOp::All,
                     };
-                    app.filter_metadata(filter_op, &lesson_metadata, &course_metadata);
+                    app.filter_metadata(
+                        filter_op,
+                        &lesson_metadata,
+                        &course_metadata,
+                        &exclude_lesson_metadata,
+                        &exclude_course_metadata,
+                    );
                     println!("Set the unit filter to only show exercises with the given metadata");
                     Ok(true)
                 }
@@ -628,14 +1781,19 @@ impl TraneCli {
                 );
                     Ok(true)
                 }
-                FilterSubcommands::Dependents { ids } => {
-                    app.filter_dependents(&ids)?;
+                FilterSubcommands::Dependents { ids, depth } => {
+                    app.filter_dependents(&ids, depth)?;
                     println!(
                     "Set the unit filter to only show exercises from the given units and their \
-                    dependencies"
+                    dependents"
                 );
                     Ok(true)
                 }
+                FilterSubcommands::Save { id, description } => {
+                    app.save_filter(&id, &description.join(" "))?;
+                    println!("Saved the unit filter with ID {id}");
+                    Ok(true)
+                }
                 FilterSubcommands::Set { id } => {
                     app.set_filter(&id)?;
                     println!("Set the unit filter to the saved filter with ID {id}");
@@ -647,6 +1805,41 @@ impl TraneCli {
                 }
             },
 
+            Subcommands::Flip { state } => {
+                app.set_flip(state.is_on());
+                if state.is_on() {
+                    println!("Flashcards will be shown back to front");
+                } else {
+                    println!("Flashcards will be shown front to back");
+                }
+                Ok(true)
+            }
+
+            Subcommands::Focus { state } => {
+                app.set_focus_mode(state.is_on());
+                if state.is_on() {
+                    println!("Focus mode is on");
+                } else {
+                    println!("Focus mode is off");
+                }
+                Ok(true)
+            }
+
+            Subcommands::Goto { position } => {
+                app.goto(position)?;
+                Ok(true)
+            }
+
+            Subcommands::Grade => {
+                app.grade()?;
+                Ok(true)
+            }
+
+            Subcommands::Hint => {
+                app.show_hint()?;
+                Ok(true)
+            }
+
             Subcommands::Instructions(subcommand) => match subcommand {
                 InstructionSubcommands::Course { course_id } => {
                     app.show_course_instructions(course_id)?;
@@ -658,25 +1851,60 @@ impl TraneCli {
                 }
             },
 
+            Subcommands::Interrupt { behavior } => {
+                app.set_interrupt_behavior(behavior);
+                if let Some(value) = behavior.to_possible_value() {
+                    println!("Set the Ctrl-C behavior to {}", value.get_name());
+                }
+                Ok(true)
+            }
+
+            Subcommands::Lifetime => {
+                app.show_lifetime()?;
+                Ok(true)
+            }
+
             Subcommands::List(subcommand) => match subcommand {
-                ListSubcommands::Courses => {
-                    app.list_courses()?;
+                ListSubcommands::Courses {
+                    mastery,
+                    progress,
+                    count,
+                } => {
+                    app.list_courses(mastery, progress, count)?;
                     Ok(true)
                 }
-                ListSubcommands::Dependencies { unit_id } => {
-                    app.list_dependencies(unit_id)?;
+                ListSubcommands::Dependencies {
+                    unit_id,
+                    mastery,
+                    count,
+                } => {
+                    app.list_dependencies(unit_id, mastery, count)?;
                     Ok(true)
                 }
-                ListSubcommands::Dependents { unit_id } => {
-                    app.list_dependents(unit_id)?;
+                ListSubcommands::Dependents {
+                    unit_id,
+                    mastery,
+                    count,
+                } => {
+                    app.list_dependents(unit_id, mastery, count)?;
                     Ok(true)
                 }
-                ListSubcommands::Exercises { lesson_id } => {
-                    app.list_exercises(lesson_id)?;
+                ListSubcommands::Exercises {
+                    lesson_id,
+                    mastery,
+                    count,
+                    with_scores,
+                } => {
+                    app.list_exercises(lesson_id, mastery, count, with_scores)?;
                     Ok(true)
                 }
-                ListSubcommands::Lessons { course_id } => {
-                    app.list_lessons(course_id)?;
+                ListSubcommands::Lessons {
+                    course_id,
+                    mastery,
+                    progress,
+                    count,
+                } => {
+                    app.list_lessons(course_id, mastery, progress, count)?;
                     Ok(true)
                 }
                 ListSubcommands::MatchingCourses => {
@@ -700,32 +1928,103 @@ impl TraneCli {
                 }
             },
 
-            Subcommands::MantraCount => {
-                app.show_mantra_count()?;
+            Subcommands::MantraCount { lifetime } => {
+                app.show_mantra_count(lifetime)?;
                 Ok(true)
             }
 
-            Subcommands::Next => {
+            Subcommands::Next { score } => {
+                if let Some(score) = score {
+                    app.record_score(score.value, None)?;
+                    app.show_answer_after_score()?;
+                }
                 app.next()?;
                 Ok(true)
             }
 
+            Subcommands::Note(subcommand) => match subcommand {
+                NoteSubcommands::Add { text } => {
+                    app.add_note(&text)?;
+                    println!("Added note to the current exercise");
+                    Ok(true)
+                }
+                NoteSubcommands::Clear => {
+                    app.clear_notes()?;
+                    println!("Cleared the notes for the current exercise");
+                    Ok(true)
+                }
+                NoteSubcommands::Show => {
+                    app.show_notes()?;
+                    Ok(true)
+                }
+            },
+
             Subcommands::Open { library_path } => {
                 app.open_library(&library_path)?;
                 println!("Successfully opened course library at {library_path}");
                 Ok(true)
             }
 
-            Subcommands::Quit => Ok(false),
+            Subcommands::OpenAsset => {
+                app.open_asset()?;
+                Ok(true)
+            }
+
+            Subcommands::Practice { exercise_id, unit } => {
+                match unit {
+                    Some(PracticeSubcommands::Course { course_id }) => {
+                        app.practice_unit(course_id, &UnitType::Course)?;
+                    }
+                    Some(PracticeSubcommands::Lesson { lesson_id }) => {
+                        app.practice_unit(lesson_id, &UnitType::Lesson)?;
+                    }
+                    None => app.practice(exercise_id.unwrap_or_default())?,
+                }
+                Ok(true)
+            }
+
+            Subcommands::Prev => {
+                app.prev()?;
+                Ok(true)
+            }
+
+            Subcommands::Preview { count } => {
+                app.preview(count)?;
+                Ok(true)
+            }
+
+            Subcommands::Prompt { state } => {
+                app.set_course_in_prompt(state.is_on());
+                if state.is_on() {
+                    println!("The course ID of the current exercise will be shown in the prompt");
+                } else {
+                    println!("The course ID will not be shown in the prompt");
+                }
+                Ok(true)
+            }
+
+            Subcommands::Quit { discard } => {
+                app.settle_pending_score(discard)?;
+                Ok(false)
+            }
+
+            Subcommands::Remaining { target } => {
+                app.show_remaining(target)?;
+                Ok(true)
+            }
 
             Subcommands::Repository(subcommand) => match subcommand {
-                RepositorySubcommands::Add { url, repo_id } => {
-                    app.add_repo(&url, repo_id)?;
+                RepositorySubcommands::Add {
+                    url,
+                    repo_id,
+                    git_ref,
+                } => {
+                    app.add_repo(&url, repo_id, git_ref.as_deref())?;
                     println!("Added repository with {url} to the course library");
                     Ok(true)
                 }
-                RepositorySubcommands::List => {
-                    app.list_repos()?;
+                RepositorySubcommands::List { verbose } => {
+                    app.list_repos(verbose)?;
                     Ok(true)
                 }
                 RepositorySubcommands::Remove { repo_id } => {
@@ -738,9 +2037,13 @@ impl TraneCli {
                     println!("Updated repository with ID {repo_id}.");
                     Ok(true)
                 }
-                RepositorySubcommands::UpdateAll => {
-                    app.update_all_repos()?;
-                    println!("Updated all managed repositories.");
+                RepositorySubcommands::UpdateAll { dry_run } => {
+                    if dry_run {
+                        app.check_repo_updates()?;
+                    } else {
+                        app.update_all_repos()?;
+                        println!("Updated all managed repositories.");
+                    }
                     Ok(true)
                 }
             },
@@ -751,39 +2054,78 @@ impl TraneCli {
                 Ok(true)
             }
 
+            Subcommands::RevealDelay { seconds } => {
+                if let Some(seconds) = seconds {
+                    app.set_reveal_delay(seconds);
+                }
+                match app.reveal_delay() {
+                    0 => println!("The answer command shows the answer right away"),
+                    1 => println!("The answer command waits 1 second before the answer"),
+                    seconds => {
+                        println!("The answer command waits {seconds} seconds before the answer");
+                    }
+                }
+                Ok(true)
+            }
+
             Subcommands::ReviewList(subcommand) => match subcommand {
-                ReviewListSubcommands::Add { unit_id } => {
-                    app.add_to_review_list(unit_id)?;
-                    println!("Added unit {unit_id} to the review list.");
+                ReviewListSubcommands::Add { unit_ids } => {
+                    app.add_to_review_list(&unit_ids)?;
+                    for unit_id in unit_ids {
+                        println!("Added unit {unit_id} to the review list.");
+                    }
+                    Ok(true)
+                }
+                ReviewListSubcommands::Export { path } => {
+                    let num_entries = app.export_review_list(Path::new(&path))?;
+                    println!("Exported {num_entries} review list entries to {path}");
+                    Ok(true)
+                }
+                ReviewListSubcommands::Import { path } => {
+                    let num_entries = app.import_review_list(Path::new(&path))?;
+                    println!("Imported {num_entries} review list entries from {path}");
                     Ok(true)
                 }
                 ReviewListSubcommands::List => {
                     app.list_review_list()?;
                     Ok(true)
                 }
-                ReviewListSubcommands::Remove { unit_id } => {
-                    app.remove_from_review_list(unit_id)?;
-                    println!("Removed unit {unit_id} from the review list.");
+                ReviewListSubcommands::Remove { unit_ids } => {
+                    app.remove_from_review_list(&unit_ids)?;
+                    for unit_id in unit_ids {
+                        println!("Removed unit {unit_id} from the review list.");
+                    }
                     Ok(true)
                 }
             },
 
-            Subcommands::Search { terms } => {
-                app.search(&terms)?;
+            Subcommands::Search {
+                terms,
+                field,
+                unit_type,
+                limit,
+            } => {
+                app.search(&terms, field, unit_type, limit)?;
                 Ok(true)
             }
 
-            Subcommands::Score { score } => {
-                app.record_score(score)?;
-                println!("Recorded mastery score {score} for current exercise.");
+            Subcommands::Score { score, note } => {
+                app.record_score(score.value, (!note.is_empty()).then(|| note.join(" ")))?;
+                println!(
+                    "Recorded mastery score {} for current exercise.",
+                    score.value
+                );
+                println!("{}", score.reminder());
+                app.show_answer_after_score()?;
                 Ok(true)
             }
 
             Subcommands::Scores {
                 exercise_id,
                 num_scores,
+                graph,
             } => {
-                app.show_scores(exercise_id, num_scores)?;
+                app.show_scores(exercise_id, num_scores, graph)?;
                 Ok(true)
             }
 
@@ -793,13 +2135,48 @@ impl TraneCli {
                     println!("Reset the scheduler options to their default values");
                     Ok(true)
                 }
-                SchedulerOptionsSubcommands::Set { batch_size } => {
-                    let options = SchedulerOptions {
-                        batch_size,
-                        ..Default::default()
-                    };
+                SchedulerOptionsSubcommands::Set {
+                    batch_size,
+                    new_percentage,
+                    target_percentage,
+                    current_percentage,
+                    easy_percentage,
+                    mastered_percentage,
+                    passing_score,
+                    superseding_score,
+                    num_trials,
+                } => {
+                    // Start from the current options so that the ones not given are kept.
+                    let mut options = app.get_scheduler_options()?;
+                    if let Some(batch_size) = batch_size {
+                        options.batch_size = batch_size;
+                    }
+                    if let Some(percentage) = new_percentage {
+                        options.new_window_opts.percentage = percentage;
+                    }
+                    if let Some(percentage) = target_percentage {
+                        options.target_window_opts.percentage = percentage;
+                    }
+                    if let Some(percentage) = current_percentage {
+                        options.current_window_opts.percentage = percentage;
+                    }
+                    if let Some(percentage) = easy_percentage {
+                        options.easy_window_opts.percentage = percentage;
+                    }
+                    if let Some(percentage) = mastered_percentage {
+                        options.mastered_window_opts.percentage = percentage;
+                    }
+                    if let Some(passing_score) = passing_score {
+                        options.passing_score = PassingScoreOptions::ConstantScore(passing_score);
+                    }
+                    if let Some(superseding_score) = superseding_score {
+                        options.superseding_score = superseding_score;
+                    }
+                    if let Some(num_trials) = num_trials {
+                        options.num_trials = num_trials;
+                    }
                     app.set_scheduler_options(options)?;
-                    println!("Set the batch size to {batch_size}");
+                    println!("Updated the scheduler options");
                     Ok(true)
                 }
                 SchedulerOptionsSubcommands::Show => {
@@ -808,6 +2185,29 @@ impl TraneCli {
                 }
             },
 
+            Subcommands::Session(subcommand) => match subcommand {
+                SessionSubcommands::Stats => {
+                    app.show_session_stats();
+                    Ok(true)
+                }
+            },
+
+            Subcommands::Stats(subcommand) => match subcommand {
+                StatsSubcommands::Forecast { days } => {
+                    app.show_forecast(days)?;
+                    Ok(true)
+                }
+                StatsSubcommands::Overview { threshold } => {
+                    app.show_overview(threshold)?;
+                    Ok(true)
+                }
+            },
+
+            Subcommands::Status => {
+                app.show_status()?;
+                Ok(true)
+            }
+
             Subcommands::StudySession(subcommand) => match subcommand {
                 StudySessionSubcommands::Clear => {
                     app.clear_study_session();
@@ -829,14 +2229,58 @@ impl TraneCli {
                 }
             },
 
+            Subcommands::Suspend(subcommand) => match subcommand {
+                SuspendSubcommands::Add { unit_id, days } => {
+                    app.suspend_unit(unit_id, days)?;
+                    println!("Suspended unit {unit_id} for {days} days");
+                    Ok(true)
+                }
+                SuspendSubcommands::List => {
+                    app.list_suspensions()?;
+                    Ok(true)
+                }
+                SuspendSubcommands::Remove { unit_id } => {
+                    app.resume_unit(unit_id)?;
+                    println!("Lifted the suspension of unit {unit_id}");
+                    Ok(true)
+                }
+            },
+
             Subcommands::Transcription(subcommand) => match subcommand {
                 TranscriptionSubcommands::Download {
                     exercise_id,
+                    course,
+                    lesson,
                     redownload,
                 } => {
-                    app.download_transcription_asset(exercise_id, redownload)?;
+                    match course.or(lesson) {
+                        Some(unit_id) => app.download_transcription_assets(unit_id, redownload)?,
+                        None => app.download_transcription_asset(exercise_id, redownload)?,
+                    }
                     Ok(true)
                 }
+                TranscriptionSubcommands::Instruments(subcommand) => match subcommand {
+                    InstrumentSubcommands::Add { id, name } => {
+                        app.add_instrument(&id, &name)?;
+                        println!(
+                            "Added instrument {id}. Reopen the library for the change to take \
+                            effect"
+                        );
+                        Ok(true)
+                    }
+                    InstrumentSubcommands::List => {
+                        app.list_instruments()?;
+                        Ok(true)
+                    }
+                    InstrumentSubcommands::Remove { id } => {
+                        app.remove_instrument(&id)?;
+                        println!(
+                            "Removed instrument {id}. Reopen the library for the change to take \
+                            effect"
+                        );
+                        Ok(true)
+                    }
+                },
                 TranscriptionSubcommands::IsDownloaded { exercise_id } => {
                     app.is_transcription_asset_downloaded(exercise_id)?;
                     Ok(true)
@@ -845,7 +2289,72 @@ impl TraneCli {
                     app.transcription_path(exercise_id)?;
                     Ok(true)
                 }
+                TranscriptionSubcommands::Play {
+                    exercise_id,
+                    download,
+                    player,
+                } => {
+                    app.play_transcription(exercise_id, player.as_deref(), download)?;
+                    Ok(true)
+                }
             },
+
+            Subcommands::Undo => {
+                app.undo()?;
+                Ok(true)
+            }
+
+            Subcommands::Version => {
+                TraneApp::show_version();
+                Ok(true)
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the given string into a key-value pair and returns its parts.
+    fn parse(s: &str) -> Result<(String, String)> {
+        let pair = KeyValue::from_str(s)?;
+        Ok((pair.key, pair.value))
+    }
+
+    /// Verifies parsing a simple key-value pair.
+    #[test]
+    fn key_value_simple() -> Result<()> {
+        assert_eq!(parse("key:C")?, ("key".to_string(), "C".to_string()));
+        assert_eq!(parse(" key:C ")?, ("key".to_string(), "C".to_string()));
+        Ok(())
+    }
+
+    /// Verifies that the pair is split at the first colon, so the value keeps the rest.
+    #[test]
+    fn key_value_multiple_colons() -> Result<()> {
+        assert_eq!(
+            parse("url:https://x:8080")?,
+            ("url".to_string(), "https://x:8080".to_string())
+        );
+        assert_eq!(parse("a:b:")?, ("a".to_string(), "b:".to_string()));
+        assert_eq!(parse("a::b")?, ("a".to_string(), ":b".to_string()));
+        Ok(())
+    }
+
+    /// Verifies that pairs with an empty key or value are rejected.
+    #[test]
+    fn key_value_empty_parts() {
+        assert!(parse(":v").is_err());
+        assert!(parse("k:").is_err());
+        assert!(parse(":").is_err());
+        assert!(parse("::").is_err());
+    }
+
+    /// Verifies that values without a colon are rejected.
+    #[test]
+    fn key_value_no_colon() {
+        assert!(parse("key").is_err());
+        assert!(parse("").is_err());
+    }
+}
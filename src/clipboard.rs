@@ -0,0 +1,48 @@
+//! Contains the logic to copy text to the system clipboard by piping it to one of the clipboard
+//! commands provided by the operating system.
+
+use anyhow::{bail, Context, Result};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// The commands to try, in order, to copy text to the clipboard, along with their arguments.
+const CLIPBOARD_COMMANDS: [(&str, &[&str]); 5] = [
+    ("pbcopy", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("clip.exe", &[]),
+];
+
+/// Copies the given text to the clipboard using the first clipboard command that can be started.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    for (program, args) in CLIPBOARD_COMMANDS {
+        let Ok(mut child) = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut child_stdin) = child.stdin.take() {
+            child_stdin
+                .write_all(text.as_bytes())
+                .with_context(|| format!("failed to write to {program}"))?;
+        }
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    bail!(
+        "could not copy to the clipboard. Install one of {}",
+        CLIPBOARD_COMMANDS
+            .iter()
+            .map(|(program, _)| *program)
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
@@ -0,0 +1,134 @@
+//! Contains the logic to read the optional config file that sets the defaults used at startup. The
+//! file is written in TOML and is located at `$XDG_CONFIG_HOME/trane/config.toml`, or at
+//! `~/.config/trane/config.toml` if `XDG_CONFIG_HOME` is not set. Command-line arguments and
+//! commands entered at the prompt override the values in the file.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::{
+    env,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use crate::{cli::InterruptBehavior, display::print_warning};
+
+/// The path of the config file relative to the user's config directory.
+const CONFIG_FILE: &str = "trane/config.toml";
+
+/// The settings read from the config file. Settings missing from the file keep their defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct StartupConfig {
+    /// The path to the course library opened at startup if none is given on the command line.
+    pub library_path: Option<String>,
+
+    /// The batch size used by the scheduler, unless the library's own preferences set one.
+    pub batch_size: Option<usize>,
+
+    /// The path to the file where the command history is stored.
+    pub history_file: Option<String>,
+
+    /// Whether to show colors. Setting it to false has the same effect as setting `NO_COLOR`.
+    pub color: Option<bool>,
+
+    /// The action to take when Ctrl-C is pressed at the prompt.
+    pub interrupt: Option<InterruptBehavior>,
+
+    /// Whether to show the answer right after scoring an exercise.
+    pub auto_answer: Option<bool>,
+
+    /// The number of seconds the answer command waits before showing the answer.
+    pub reveal_delay: Option<u64>,
+}
+
+/// Returns the path to the config file, or `None` if neither `XDG_CONFIG_HOME` nor `HOME` is set.
+pub fn config_path() -> Option<PathBuf> {
+    let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_dir.join(CONFIG_FILE))
+}
+
+/// Expands a leading `~` to the home directory and every `$VAR` or `${VAR}` to the value of the
+/// environment variable, so that the same file can be used on different machines. Returns an error
+/// naming the variable if one of them is not set.
+fn expand_path(value: &str) -> Result<String> {
+    let mut expanded = String::new();
+    let mut rest = value;
+    if rest == "~" || rest.starts_with("~/") {
+        expanded.push_str(&env::var("HOME").context("HOME is not set")?);
+        rest = &rest[1..];
+    }
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        let (name, remaining) = if let Some(braced) = rest.strip_prefix('{') {
+            let Some(end) = braced.find('}') else {
+                bail!("missing closing brace in {value}");
+            };
+            (&braced[..end], &braced[end + 1..])
+        } else {
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            (&rest[..end], &rest[end..])
+        };
+
+        // Keep a lone dollar sign as is.
+        if name.is_empty() {
+            expanded.push('$');
+            continue;
+        }
+        expanded.push_str(
+            &env::var(name).with_context(|| format!("environment variable {name} is not set"))?,
+        );
+        rest = remaining;
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Expands the path in the given setting, warning about and dropping the setting if the expansion
+/// fails.
+fn expand_setting(name: &str, setting: &mut Option<String>) {
+    let Some(value) = setting.as_deref() else {
+        return;
+    };
+    match expand_path(value) {
+        Ok(expanded) => *setting = Some(expanded),
+        Err(err) => {
+            print_warning(&format!(
+                "ignored the {name} setting in the config file: {err:#}"
+            ));
+            *setting = None;
+        }
+    }
+}
+
+impl StartupConfig {
+    /// Reads the config file at the given path.
+    fn read(path: &Path) -> Result<Self> {
+        let contents = read_to_string(path)
+            .with_context(|| format!("failed to read config file at {}", path.display()))?;
+        let mut config: Self = toml::from_str(&contents)
+            .with_context(|| format!("failed to parse config file at {}", path.display()))?;
+        expand_setting("library_path", &mut config.library_path);
+        expand_setting("history_file", &mut config.history_file);
+        Ok(config)
+    }
+
+    /// Reads the config file, if it exists. If the file cannot be read or parsed, a warning is
+    /// printed and the defaults are used instead.
+    pub fn load() -> Self {
+        let Some(path) = config_path().filter(|path| path.exists()) else {
+            return Self::default();
+        };
+        Self::read(&path).unwrap_or_else(|err| {
+            print_warning(&format!("{err:#}, so the defaults are used instead"));
+            Self::default()
+        })
+    }
+}
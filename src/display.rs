@@ -2,12 +2,24 @@
 
 use anyhow::{Context, Result};
 use rand::prelude::SliceRandom;
-use std::fs::read_to_string;
-use termimad::print_inline;
+use std::{
+    fs::read_to_string,
+    io::{stdout, IsTerminal},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use termimad::{
+    crossterm::{
+        style::{Color, Stylize},
+        terminal,
+    },
+    inline, print_inline,
+};
 use trane::data::{
     course_generator::literacy::LiteracyLesson, BasicAsset, ExerciseAsset, ExerciseManifest,
 };
 
+use crate::pager::page_text;
+
 /// Prints the markdown file at the given path to the terminal.
 pub fn print_markdown(path: &str) -> Result<()> {
     let contents =
@@ -17,6 +29,209 @@ pub fn print_markdown(path: &str) -> Result<()> {
     Ok(())
 }
 
+/// Returns the given URL as a hyperlink that can be clicked in terminals that support it. The URL
+/// is returned unchanged if the output is not a terminal.
+pub fn hyperlink(url: &str) -> String {
+    if stdout().is_terminal() {
+        format!("\x1b]8;;{url}\x1b\\{url}\x1b]8;;\x1b\\")
+    } else {
+        url.to_string()
+    }
+}
+
+/// Prints the notes the user has written about an exercise.
+pub fn print_notes(notes: &[String]) {
+    if notes.is_empty() {
+        return;
+    }
+    println!();
+    println!("Notes:");
+    for note in notes {
+        println!("- {note}");
+    }
+}
+
+/// The number of warnings printed since the program started.
+static NUM_WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+/// Prints the given warning. All warnings should be printed through this function so that strict
+/// mode can detect them.
+pub fn print_warning(message: &str) {
+    NUM_WARNINGS.fetch_add(1, Ordering::Relaxed);
+    println!("Warning: {message}");
+}
+
+/// Returns the number of warnings printed since the program started.
+pub fn num_warnings() -> usize {
+    NUM_WARNINGS.load(Ordering::Relaxed)
+}
+
+/// Prints the given value as pretty-printed JSON.
+pub fn print_json(value: &serde_json::Value) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// The characters used to draw sparklines, from lowest to highest.
+const SPARKLINE_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Returns a sparkline of the given values, each drawn with a character whose height is
+/// proportional to its position between `min` and `max`.
+pub fn sparkline(values: &[f32], min: f32, max: f32) -> String {
+    let top = SPARKLINE_CHARS.len() - 1;
+    values
+        .iter()
+        .map(|value| {
+            let fraction = if max > min {
+                ((value - min) / (max - min)).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+            SPARKLINE_CHARS[(fraction * top as f32).round() as usize]
+        })
+        .collect()
+}
+
+/// Returns the width of the terminal, or `None` if it cannot be determined or is reported as zero,
+/// as some pseudo-terminals do.
+fn detected_terminal_width() -> Option<usize> {
+    terminal::size()
+        .ok()
+        .map(|(columns, _)| usize::from(columns))
+        .filter(|columns| *columns > 0)
+}
+
+/// Returns the width of the terminal, or a default width if it cannot be determined.
+pub fn terminal_width() -> usize {
+    detected_terminal_width().unwrap_or(80)
+}
+
+/// Returns the width of a table column that fits its header and its longest value. The width is
+/// capped so that each row fits in the terminal next to the other columns, which take up
+/// `other_columns` characters including the separators. Longer values are not truncated, so that
+/// IDs can still be copied in full. The given default width is used if the width of the terminal
+/// cannot be determined.
+pub fn column_width<'a>(
+    header: &str,
+    values: impl IntoIterator<Item = &'a str>,
+    other_columns: usize,
+    default: usize,
+) -> usize {
+    let Some(width) = detected_terminal_width() else {
+        return default;
+    };
+    let longest = values
+        .into_iter()
+        .map(|value| value.chars().count())
+        .chain(std::iter::once(header.chars().count()))
+        .max()
+        .unwrap_or_default();
+    longest.min(
+        width
+            .saturating_sub(other_columns)
+            .max(header.chars().count()),
+    )
+}
+
+/// Returns the given duration in seconds in the largest whole unit, from minutes to years, for
+/// example "3 days". Returns `None` if the duration is less than a minute.
+fn largest_time_unit(duration: i64) -> Option<String> {
+    const UNITS: [(&str, i64); 5] = [
+        ("year", 365 * 86_400),
+        ("month", 30 * 86_400),
+        ("day", 86_400),
+        ("hour", 3_600),
+        ("minute", 60),
+    ];
+
+    for (unit, seconds) in UNITS {
+        let count = duration / seconds;
+        if count == 1 {
+            return Some(format!("1 {unit}"));
+        }
+        if count > 1 {
+            return Some(format!("{count} {unit}s"));
+        }
+    }
+    None
+}
+
+/// Returns how long ago the given timestamp was relative to `now`, both in seconds since the Unix
+/// epoch, in the largest whole unit, for example "3 days ago". Timestamps less than a minute old or
+/// in the future, which can happen when clocks are out of sync, are shown as "just now".
+pub fn relative_time(timestamp: i64, now: i64) -> String {
+    largest_time_unit(now - timestamp).map_or_else(|| "just now".to_string(), |age| age + " ago")
+}
+
+/// Returns how long until the given timestamp relative to `now`, in the largest whole unit, for
+/// example "in 3 days". Timestamps less than a minute away are shown as "in less than a minute".
+pub fn remaining_time(timestamp: i64, now: i64) -> String {
+    largest_time_unit(timestamp - now).map_or_else(
+        || "in less than a minute".to_string(),
+        |remaining| format!("in {remaining}"),
+    )
+}
+
+/// Returns a bar of up to `width` characters whose length is proportional to `value / max`.
+pub fn ascii_bar(value: usize, max: usize, width: usize) -> String {
+    if max == 0 {
+        return String::new();
+    }
+    "#".repeat(value * width / max)
+}
+
+/// The minimum aggregate score for a unit to be considered mastered.
+const MASTERED_SCORE: f32 = 4.0;
+
+/// Returns a badge describing the aggregate mastery of a unit. The badge is green for mastered
+/// units, yellow for units at or above the passing score, and red for the rest. The text label is
+/// always included, so the badge is still readable when colors are disabled via `NO_COLOR` or when
+/// the output is not a terminal.
+pub fn mastery_badge(score: Option<f32>, passing_score: f32) -> String {
+    let Some(score) = score else {
+        return "unseen".to_string();
+    };
+    let (label, color) = if score >= MASTERED_SCORE {
+        ("mastered", Color::Green)
+    } else if score >= passing_score {
+        ("passing", Color::Yellow)
+    } else {
+        ("failing", Color::Red)
+    };
+
+    let badge = format!("{label} ({score:.2})");
+    if stdout().is_terminal() {
+        badge.with(color).to_string()
+    } else {
+        badge
+    }
+}
+
+/// Returns the given text colored according to the given score: red for scores below three,
+/// yellow for scores below four, and green for the rest. If `bold` is true, the text is also shown
+/// in bold. The text is returned unchanged if the output is not a terminal or if the `NO_COLOR`
+/// environment variable is set.
+pub fn color_by_score(text: &str, score: f32, bold: bool) -> String {
+    if !stdout().is_terminal()
+        || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+    {
+        return text.to_string();
+    }
+    let color = if score >= 4.0 {
+        Color::Green
+    } else if score >= 3.0 {
+        Color::Yellow
+    } else {
+        Color::Red
+    };
+    let styled = text.with(color);
+    if bold {
+        styled.bold().to_string()
+    } else {
+        styled.to_string()
+    }
+}
+
 /// Randomly samples five values from the given list of strings.
 fn sample(values: &[String]) -> Vec<String> {
     let mut sampled = values.to_vec();
@@ -58,20 +273,22 @@ pub trait DisplayAsset {
     fn display_asset(&self) -> Result<()>;
 }
 
+/// Renders the given markdown and prints it through the pager.
+fn page_markdown(contents: &str) -> Result<()> {
+    page_text(&format!("{}\n", inline(contents)))
+}
+
 impl DisplayAsset for BasicAsset {
+    /// Prints the asset through the pager, since instructions and material can be long.
     fn display_asset(&self) -> Result<()> {
         match self {
-            BasicAsset::MarkdownAsset { path } => print_markdown(path),
-            BasicAsset::InlinedAsset { content } => {
-                print_inline(content);
-                println!();
-                Ok(())
-            }
-            BasicAsset::InlinedUniqueAsset { content } => {
-                print_inline(content);
-                println!();
-                Ok(())
+            BasicAsset::MarkdownAsset { path } => {
+                let contents = read_to_string(path)
+                    .with_context(|| format!("Failed to read file at path: {path}"))?;
+                page_markdown(&contents)
             }
+            BasicAsset::InlinedAsset { content } => page_markdown(content),
+            BasicAsset::InlinedUniqueAsset { content } => page_markdown(content),
         }
     }
 }
@@ -103,7 +320,7 @@ impl DisplayExercise for ExerciseAsset {
                     print_inline(description);
                     println!();
                 }
-                println!("SoundSlice link: {link}");
+                println!("SoundSlice link: {}", hyperlink(link));
                 Ok(())
             }
             ExerciseAsset::TranscriptionAsset { content, .. } => {
@@ -115,21 +332,45 @@ impl DisplayExercise for ExerciseAsset {
     }
 }
 
+/// Prints the exercise's description and asset without the header containing the IDs.
+pub fn display_exercise_content(manifest: &ExerciseManifest) -> Result<()> {
+    if let Some(description) = &manifest.description {
+        println!("Exercise description: {description}");
+        println!();
+    }
+    manifest.exercise_asset.display_exercise()
+}
+
 impl DisplayExercise for ExerciseManifest {
     fn display_exercise(&self) -> Result<()> {
         println!("Course ID: {}", self.course_id);
         println!("Lesson ID: {}", self.lesson_id);
         println!("Exercise ID: {}", self.id);
         println!();
-        if let Some(description) = &self.description {
-            println!("Exercise description: {description}");
-            println!();
-        }
-        self.exercise_asset.display_exercise()?;
-        Ok(())
+        display_exercise_content(self)
     }
 }
 
+/// Returns a copy of the exercise with the front and back of its flashcard swapped, so that the
+/// back is shown as the prompt and the front as the answer. Returns `None` if the exercise is not a
+/// flashcard or if its flashcard has no back.
+pub fn flip_flashcard(manifest: &ExerciseManifest) -> Option<ExerciseManifest> {
+    let ExerciseAsset::FlashcardAsset {
+        front_path,
+        back_path: Some(back_path),
+    } = &manifest.exercise_asset
+    else {
+        return None;
+    };
+    Some(ExerciseManifest {
+        exercise_asset: ExerciseAsset::FlashcardAsset {
+            front_path: back_path.clone(),
+            back_path: Some(front_path.clone()),
+        },
+        ..manifest.clone()
+    })
+}
+
 /// Trait to display an exercise's answer in the terminal.
 pub trait DisplayAnswer {
     /// Prints the exercise's answer to the terminal.
@@ -139,11 +380,24 @@ pub trait DisplayAnswer {
 impl DisplayAnswer for ExerciseAsset {
     fn display_answer(&self) -> Result<()> {
         match self {
-            ExerciseAsset::BasicAsset(_) | ExerciseAsset::TranscriptionAsset { .. } => {
+            ExerciseAsset::BasicAsset(_)
+            | ExerciseAsset::TranscriptionAsset {
+                external_link: None,
+                ..
+            } => {
                 println!("No answer available for this exercise.");
                 println!();
                 Ok(())
             }
+            ExerciseAsset::TranscriptionAsset {
+                external_link: Some(link),
+                ..
+            } => {
+                println!("Compare your transcription with the recording:");
+                println!();
+                println!("Recording link: {}", hyperlink(link.url()));
+                Ok(())
+            }
             ExerciseAsset::FlashcardAsset { back_path, .. } => {
                 if let Some(back_path) = back_path {
                     println!("Answer:");
@@ -169,3 +423,74 @@ impl DisplayAnswer for ExerciseManifest {
         Ok(())
     }
 }
+
+/// Prints the given text between delimiters marking its beginning and end, so that it can be
+/// easily copied.
+fn print_delimited(label: &str, contents: &str) {
+    println!("----- BEGIN {label} -----");
+    println!("{}", contents.trim_end());
+    println!("----- END {label} -----");
+}
+
+/// Prints the raw contents of the file at the given path between delimiters.
+fn print_raw_file(label: &str, path: &str) -> Result<()> {
+    let contents =
+        read_to_string(path).with_context(|| format!("Failed to read file at path: {path}"))?;
+    print_delimited(&format!("{label} ({path})"), &contents);
+    Ok(())
+}
+
+/// Prints the manifest of the exercise as JSON followed by the raw contents of the files
+/// referenced by its asset, without any rendering. The output is meant to be pasted into bug
+/// reports.
+pub fn dump_exercise(manifest: &ExerciseManifest) -> Result<()> {
+    print_delimited("MANIFEST", &serde_json::to_string_pretty(manifest)?);
+    match &manifest.exercise_asset {
+        ExerciseAsset::BasicAsset(BasicAsset::MarkdownAsset { path }) => {
+            print_raw_file("ASSET", path)?;
+        }
+        ExerciseAsset::FlashcardAsset {
+            front_path,
+            back_path,
+        } => {
+            print_raw_file("FRONT", front_path)?;
+            if let Some(back_path) = back_path {
+                print_raw_file("BACK", back_path)?;
+            }
+        }
+        ExerciseAsset::BasicAsset(BasicAsset::InlinedAsset { content })
+        | ExerciseAsset::TranscriptionAsset { content, .. } => {
+            print_delimited("ASSET", content);
+        }
+        ExerciseAsset::BasicAsset(BasicAsset::InlinedUniqueAsset { content }) => {
+            print_delimited("ASSET", content);
+        }
+        ExerciseAsset::SoundSliceAsset { backup, .. } => {
+            if let Some(backup) = backup {
+                println!("SoundSlice backup path: {backup}");
+            }
+        }
+        ExerciseAsset::LiteracyAsset { .. } => {}
+    }
+    Ok(())
+}
+
+/// Prints the given exercise through every display mode, one after the other, each preceded by a
+/// label. The modes are the standard view, the focus mode view without IDs, and the raw view used
+/// for bug reports. Both the exercise and the answer are shown in the first two modes.
+pub fn render_exercise(manifest: &ExerciseManifest) -> Result<()> {
+    println!("===== STANDARD =====");
+    manifest.display_exercise()?;
+    println!();
+    manifest.exercise_asset.display_answer()?;
+    println!();
+
+    println!("===== FOCUS MODE =====");
+    display_exercise_content(manifest)?;
+    println!();
+    manifest.exercise_asset.display_answer()?;
+    println!();
+
+    println!("===== RAW =====");
+    dump_exercise(manifest)
+}
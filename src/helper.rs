@@ -2,23 +2,58 @@
 //! Inspired by `<https://github.com/kkawakam/rustyline/blob/master/examples/example.rs>`
 //! this mod work for Completer and Prompt.
 
-use rustyline::completion::FilenameCompleter;
+use clap::{Arg, Command, CommandFactory};
+use rustyline::completion::{Completer, FilenameCompleter, Pair};
 use rustyline::highlight::{Highlighter, MatchingBracketHighlighter};
 use rustyline::hint::HistoryHinter;
 use rustyline::validate::MatchingBracketValidator;
-use rustyline_derive::{Completer, Helper, Hinter, Validator};
+use rustyline::Context;
+use rustyline_derive::{Helper, Hinter, Validator};
 use std::borrow::Cow::{self, Borrowed, Owned};
+use std::{cell::RefCell, rc::Rc};
+use ustr::Ustr;
+
+use crate::cli::TraneCli;
+
+/// The IDs of the units in the open library, used to complete unit IDs.
+#[derive(Default)]
+pub struct UnitIds {
+    /// The IDs of all the courses.
+    pub courses: Vec<Ustr>,
+
+    /// The IDs of all the lessons.
+    pub lessons: Vec<Ustr>,
+
+    /// The IDs of all the exercises.
+    pub exercises: Vec<Ustr>,
+}
+
+/// A handle to the unit IDs shared between the app, which updates them when a library is opened,
+/// and the helper, which reads them during completion.
+pub type SharedUnitIds = Rc<RefCell<UnitIds>>;
+
+/// The escape sequence used to highlight the name of a known command.
+const KNOWN_COMMAND_COLOR: &str = "\x1b[1;32m";
+
+/// The escape sequence used to highlight the name of an unknown command.
+const UNKNOWN_COMMAND_COLOR: &str = "\x1b[1;31m";
 
 /// A custom helper for Trane's command-line interface.
-#[derive(Helper, Completer, Hinter, Validator)]
+#[derive(Helper, Hinter, Validator)]
 pub struct MyHelper {
-    #[rustyline(Completer)]
     completer: FilenameCompleter,
     highlighter: MatchingBracketHighlighter,
     #[rustyline(Validator)]
     validator: MatchingBracketValidator,
     #[rustyline(Hinter)]
     hinter: HistoryHinter,
+
+    /// The definition of the commands, used to complete command names and to find which argument
+    /// is being completed.
+    command: Command,
+
+    /// The IDs of the units in the open library.
+    unit_ids: SharedUnitIds,
 }
 
 impl Highlighter for MyHelper {
@@ -40,25 +75,173 @@ impl Highlighter for MyHelper {
         Owned("\x1b[1m".to_owned() + hint + "\x1b[m")
     }
 
-    /// Custom logic to highlight the current line.
+    /// Custom logic to highlight the current line. The command name is highlighted in green if it's
+    /// a known command and in red otherwise, and matching brackets are highlighted in the rest of
+    /// the line.
     fn highlight<'l>(&self, line: &'l str, pos: usize) -> Cow<'l, str> {
-        self.highlighter.highlight(line, pos)
+        let highlighted = self.highlighter.highlight(line, pos);
+        let Some((start, end)) = Self::command_name_range(line) else {
+            return highlighted;
+        };
+
+        // Leave the line as is if the bracket highlighting changed the command name.
+        if !highlighted.starts_with(&line[..end]) {
+            return highlighted;
+        }
+        let name = &line[start..end];
+        let color = if self.command.find_subcommand(name).is_some() {
+            KNOWN_COMMAND_COLOR
+        } else {
+            UNKNOWN_COMMAND_COLOR
+        };
+        Owned(format!(
+            "{}{color}{name}\x1b[0m{}",
+            &line[..start],
+            &highlighted[end..]
+        ))
     }
 
-    /// Custom logic to highlight the current character.
+    /// Custom logic to highlight the current character. The line is also highlighted again while
+    /// the command name is being edited so that its color is updated.
     fn highlight_char(&self, line: &str, pos: usize, force: bool) -> bool {
-        self.highlighter.highlight_char(line, pos, force)
+        let bracket = self.highlighter.highlight_char(line, pos, force);
+        let in_command_name = Self::command_name_range(line).is_some_and(|(_, end)| pos <= end);
+        bracket || (!force && in_command_name)
+    }
+}
+
+impl Completer for MyHelper {
+    type Candidate = Pair;
+
+    /// Completes subcommand names by walking the command tree, and unit IDs for the arguments that
+    /// expect them. Other arguments are completed as file names.
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos].rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let prefix = &line[start..pos];
+
+        // Find the innermost command and the number of positional arguments already written.
+        let mut command = &self.command;
+        let mut num_positionals = 0;
+        for (index, word) in line[..start].split_whitespace().enumerate() {
+            if word.starts_with('-') || (index == 0 && word == "trane") {
+                continue;
+            }
+            match command.find_subcommand(word) {
+                Some(subcommand) if num_positionals == 0 => command = subcommand,
+                _ => num_positionals += 1,
+            }
+        }
+
+        // Complete the names of the subcommands, along with the unit IDs if the command also takes
+        // them as its first argument.
+        if command.has_subcommands() && num_positionals == 0 {
+            let mut candidates: Vec<Pair> = command
+                .get_subcommands()
+                .map(Command::get_name)
+                .filter(|name| *name != "help" && name.starts_with(prefix))
+                .map(|name| Pair {
+                    display: name.to_string(),
+                    replacement: format!("{name} "),
+                })
+                .collect();
+            if let Some(unit_ids) = command
+                .get_positionals()
+                .next()
+                .and_then(|arg| self.unit_id_candidates(command, arg, prefix))
+            {
+                candidates.extend(unit_ids);
+            }
+            return Ok((start, candidates));
+        }
+
+        // Complete the unit IDs if the argument expects them. A positional argument that takes
+        // multiple values absorbs all the remaining words.
+        let arg = command.get_positionals().nth(num_positionals).or_else(|| {
+            command
+                .get_positionals()
+                .last()
+                .filter(|arg| Self::takes_multiple_values(arg))
+        });
+        if let Some(unit_ids) = arg.and_then(|arg| self.unit_id_candidates(command, arg, prefix)) {
+            return Ok((start, unit_ids));
+        }
+        self.completer.complete(line, pos, ctx)
     }
 }
 
 impl MyHelper {
-    /// Creates a new `MyHelper` instance.
-    pub fn new() -> Self {
+    /// Creates a new `MyHelper` instance that completes unit IDs from the given handle.
+    pub fn new(unit_ids: SharedUnitIds) -> Self {
+        let mut command = TraneCli::command();
+        command.build();
         MyHelper {
             completer: FilenameCompleter::new(),
             highlighter: MatchingBracketHighlighter::new(),
             hinter: HistoryHinter {},
             validator: MatchingBracketValidator::new(),
+            command,
+            unit_ids,
         }
     }
+
+    /// Returns the start and end of the first word in the line at or after the given position.
+    fn word_range(line: &str, from: usize) -> (usize, usize) {
+        let start = line.len() - line[from..].trim_start().len();
+        let end = line[start..]
+            .find(char::is_whitespace)
+            .map_or(line.len(), |index| start + index);
+        (start, end)
+    }
+
+    /// Returns the byte range of the command name in the line, skipping the optional `trane`
+    /// prefix, or `None` if the line has no command name yet.
+    fn command_name_range(line: &str) -> Option<(usize, usize)> {
+        let (mut start, mut end) = Self::word_range(line, 0);
+        if &line[start..end] == "trane" {
+            (start, end) = Self::word_range(line, end);
+        }
+        if start == end || line[start..].starts_with('-') {
+            None
+        } else {
+            Some((start, end))
+        }
+    }
+
+    /// Returns whether the argument accepts more than one value.
+    fn takes_multiple_values(arg: &Arg) -> bool {
+        arg.get_num_args()
+            .is_some_and(|range| range.max_values() > 1)
+    }
+
+    /// Returns the unit IDs starting with the given prefix that are valid values for the argument
+    /// of the given command, or `None` if the argument does not take unit IDs.
+    fn unit_id_candidates(&self, command: &Command, arg: &Arg, prefix: &str) -> Option<Vec<Pair>> {
+        let unit_ids = self.unit_ids.borrow();
+        let ids: Vec<&Ustr> = match (command.get_name(), arg.get_id().as_str()) {
+            (_, "course_id") | ("courses", "ids") => unit_ids.courses.iter().collect(),
+            (_, "lesson_id") | ("lessons", "ids") => unit_ids.lessons.iter().collect(),
+            (_, "exercise_id") | ("exercises", "ids") => unit_ids.exercises.iter().collect(),
+            (_, "unit_id" | "unit_ids" | "ids") => unit_ids
+                .courses
+                .iter()
+                .chain(&unit_ids.lessons)
+                .chain(&unit_ids.exercises)
+                .collect(),
+            _ => return None,
+        };
+        Some(
+            ids.into_iter()
+                .filter(|id| id.starts_with(prefix))
+                .map(|id| Pair {
+                    display: id.to_string(),
+                    replacement: format!("{id} "),
+                })
+                .collect(),
+        )
+    }
 }
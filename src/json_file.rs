@@ -0,0 +1,34 @@
+//! Contains the helpers used to read and write the JSON files in which the CLI stores its own data
+//! inside the library's config directory.
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fs::{read_to_string, write},
+    path::Path,
+};
+
+/// Reads the JSON file at the given path, or returns `None` if the file does not exist. The
+/// description is used in the error messages.
+pub(crate) fn read_json<T: DeserializeOwned>(path: &Path, description: &str) -> Result<Option<T>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = read_to_string(path)
+        .with_context(|| format!("failed to read {description} file at {}", path.display()))?;
+    let value = serde_json::from_str(&contents)
+        .with_context(|| format!("failed to parse {description} file at {}", path.display()))?;
+    Ok(Some(value))
+}
+
+/// Writes the given value as pretty-printed JSON to the given path. The description is used in the
+/// error messages.
+pub(crate) fn write_json<T: Serialize + ?Sized>(
+    path: &Path,
+    value: &T,
+    description: &str,
+) -> Result<()> {
+    let contents = serde_json::to_string_pretty(value)? + "\n";
+    write(path, contents)
+        .with_context(|| format!("failed to write {description} file at {}", path.display()))
+}
@@ -0,0 +1,123 @@
+//! Contains the logic to read single key presses from the terminal, used by the modes that do not
+//! go through the line-based prompt.
+
+use anyhow::Result;
+use std::{
+    io::{stdin, stdout, IsTerminal, Write},
+    time::{Duration, Instant},
+};
+use termimad::crossterm::{
+    event::{poll, read, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
+
+/// The keys recognized while in grade mode.
+pub(crate) enum GradeKey {
+    /// Record the given score and move on to the next exercise.
+    Score(u8),
+
+    /// Show the answer to the current exercise.
+    Reveal,
+
+    /// Leave grade mode.
+    Exit,
+}
+
+/// Disables the terminal's raw mode when dropped, so that the terminal is restored even if reading
+/// a key fails.
+pub(crate) struct RawModeGuard;
+
+impl RawModeGuard {
+    /// Enables raw mode and returns a guard that disables it when dropped.
+    pub fn new() -> Result<Self> {
+        enable_raw_mode()?;
+        Ok(RawModeGuard)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+    }
+}
+
+/// Waits for a key press recognized by grade mode without waiting for the Enter key. Other keys
+/// are ignored.
+pub(crate) fn read_grade_key() -> Result<GradeKey> {
+    let _guard = RawModeGuard::new()?;
+    loop {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = read()?
+        else {
+            continue;
+        };
+
+        match code {
+            KeyCode::Char(c @ '1'..='5') => return Ok(GradeKey::Score(c as u8 - b'0')),
+            KeyCode::Char(' ') | KeyCode::Enter => return Ok(GradeKey::Reveal),
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(GradeKey::Exit)
+            }
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(GradeKey::Exit),
+            _ => {}
+        }
+    }
+}
+
+/// Counts down the given number of seconds, updating the remaining time on a single line. Pressing
+/// Ctrl-C, Escape, or `q` cancels the countdown. Returns whether the countdown finished without
+/// being cancelled. If the input is not a terminal, such as when running a script, there is no one
+/// to wait for, so the function returns right away.
+pub(crate) fn countdown(seconds: u64) -> Result<bool> {
+    if !stdin().is_terminal() {
+        return Ok(true);
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(seconds);
+    let guard = RawModeGuard::new()?;
+    let finished = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break true;
+        }
+        let remaining_seconds = remaining.as_secs_f32().ceil();
+        let unit = if remaining_seconds > 1.0 {
+            "seconds"
+        } else {
+            "second"
+        };
+        print!("\rThe answer will be shown in {remaining_seconds} {unit}, press Ctrl-C to cancel ");
+        stdout().flush()?;
+
+        // Wait until the next whole second or until a key is pressed.
+        let tick = Duration::from_nanos(u64::from(remaining.subsec_nanos()));
+        let tick = if tick.is_zero() {
+            Duration::from_secs(1)
+        } else {
+            tick
+        };
+        if !poll(tick)? {
+            continue;
+        }
+        if let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = read()?
+        {
+            match code {
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => break false,
+                KeyCode::Esc | KeyCode::Char('q') => break false,
+                _ => {}
+            }
+        }
+    };
+    drop(guard);
+    println!();
+    Ok(finished)
+}
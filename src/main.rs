@@ -11,41 +11,294 @@
 #![allow(clippy::cast_precision_loss)]
 #![allow(clippy::too_many_lines)]
 
+mod active_filter;
 mod app;
+mod bookmarks;
 mod built_info {
     // The file has been placed there by the build script.
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
 mod cli;
+mod clipboard;
+mod config;
 mod display;
 mod helper;
+mod json_file;
+mod keys;
+mod mantras;
+mod notes;
+mod pager;
+mod picker;
+mod recent;
+mod repositories;
+mod session;
+mod suspensions;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use app::TraneApp;
 use clap::Parser;
 use helper::MyHelper;
 use rustyline::error::ReadlineError;
-use rustyline::history::FileHistory;
+use rustyline::history::{FileHistory, History};
 use rustyline::{ColorMode, Config, Editor};
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
-use crate::cli::TraneCli;
+use crate::{
+    cli::{InterruptBehavior, TraneArgs, TraneCli},
+    config::StartupConfig,
+};
+
+/// The name of the file in the working directory where the command history is stored.
+const HISTORY_FILE: &str = ".trane_history";
+
+/// Returns the path of the backup made for the history file at the given path, which appends
+/// `.bak` to the full file name.
+fn history_backup_path(history_path: &Path) -> PathBuf {
+    let mut backup_path = history_path.as_os_str().to_owned();
+    backup_path.push(".bak");
+    PathBuf::from(backup_path)
+}
+
+/// Loads the history from the given file. If the file cannot be decoded, it's assumed to be
+/// corrupt, so it's moved to a backup file and replaced with an empty one so that saving the
+/// history works from now on. Other errors, such as missing permissions, leave the file untouched.
+/// Returns the path to the backup if one was made.
+fn load_history(history: &mut FileHistory, history_path: &Path) -> Option<PathBuf> {
+    let Err(e) = history.load(history_path) else {
+        return None;
+    };
+    eprintln!(
+        "Failed to load history file at {}: {e}",
+        history_path.display()
+    );
+    if !matches!(&e, ReadlineError::Io(err) if err.kind() == ErrorKind::InvalidData) {
+        return None;
+    }
+
+    let backup_path = history_backup_path(history_path);
+    if let Err(e) = std::fs::rename(history_path, &backup_path) {
+        eprintln!("Failed to back up history file: {e}");
+        return None;
+    }
+    let _ = history.clear();
+    match std::fs::File::create(history_path) {
+        Ok(_) => eprintln!(
+            "The history file might be corrupt. It was moved to {} and a new history was started",
+            backup_path.display()
+        ),
+        Err(e) => eprintln!("Failed to create history file: {e}"),
+    }
+    Some(backup_path)
+}
+
+/// The exit code used when a command in a script fails.
+const EXIT_COMMAND_FAILED: i32 = 1;
+
+/// The exit code used when the script cannot be read or the library cannot be opened. Invalid
+/// command-line arguments exit with code 2, as reported by clap.
+const EXIT_SETUP_FAILED: i32 = 3;
+
+/// The exit code used in strict mode when a command in a script prints a warning but no command
+/// fails.
+const EXIT_WARNING: i32 = 4;
+
+/// The outcome of executing a line entered at the prompt or read from a script.
+enum LineResult {
+    /// The line was executed, or skipped because it was empty or a comment.
+    Continue,
+
+    /// The line could not be parsed or the command failed. The error has already been printed.
+    Failed,
+
+    /// The command asked to exit the program.
+    Quit,
+}
+
+/// Parses and executes a single command line, printing any errors.
+fn execute_line(app: &mut TraneApp, line: &str) -> LineResult {
+    // Trim any blank space from the line.
+    let line = line.trim();
+
+    // Ignore comments and empty lines.
+    if line.starts_with('#') || line.eq("") {
+        return LineResult::Continue;
+    }
+
+    // Split the line into a vector of arguments. Add an initial argument with value "trane" if
+    // the line doesn't have it, so the parser can recognize the input.
+    let split: Vec<&str> = line.split(' ').collect();
+    let mut args = if !split.is_empty() && split[0] == "trane" {
+        vec![]
+    } else {
+        vec!["trane"]
+    };
+    args.extend(split);
+
+    // Parse the arguments.
+    let cli = TraneCli::try_parse_from(args.iter());
+    if cli.is_err() {
+        // Suggest the closest command if the first word is a likely typo.
+        let suggestion = args
+            .get(1)
+            .filter(|name| !name.starts_with('-'))
+            .and_then(|name| TraneCli::suggest_command(name).map(|suggestion| (name, suggestion)));
+        if let Some((name, suggestion)) = suggestion {
+            println!("unknown command '{name}'; did you mean '{suggestion}'?");
+        } else {
+            println!("{}", cli.unwrap_err());
+        }
+        return LineResult::Failed;
+    }
+
+    // Execute the subcommand, timing it if requested.
+    let cli = cli.unwrap();
+    let start = Instant::now();
+    let result = cli.execute_subcommand(app);
+    if cli.time {
+        eprintln!("Command took {:.3?}", start.elapsed());
+    }
+    match result {
+        Ok(true) => LineResult::Continue,
+        Ok(false) => LineResult::Quit,
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            LineResult::Failed
+        }
+    }
+}
+
+/// Executes the commands in the given script file, one per line. Execution stops at the first
+/// command that fails unless `continue_on_error` is true. If `strict` is true, commands that print
+/// a warning are treated as failed. Returns the exit code for the script: zero if all the commands
+/// succeeded, `EXIT_COMMAND_FAILED` if any command failed, or `EXIT_WARNING` if the only failures
+/// were warnings.
+fn run_script(
+    app: &mut TraneApp,
+    script_path: &str,
+    continue_on_error: bool,
+    strict: bool,
+) -> Result<i32> {
+    let contents = std::fs::read_to_string(script_path)
+        .with_context(|| format!("failed to read script at {script_path}"))?;
+    let mut exit_code = 0;
+    for (line_number, line) in (1..).zip(contents.lines()) {
+        let num_warnings = display::num_warnings();
+        let line_exit_code = match execute_line(app, line) {
+            LineResult::Continue if strict && display::num_warnings() > num_warnings => {
+                EXIT_WARNING
+            }
+            LineResult::Continue => 0,
+            LineResult::Quit => break,
+            LineResult::Failed => EXIT_COMMAND_FAILED,
+        };
+        if line_exit_code == 0 {
+            continue;
+        }
+        if exit_code != EXIT_COMMAND_FAILED {
+            exit_code = line_exit_code;
+        }
+        if !continue_on_error {
+            eprintln!("Stopped the script at line {line_number}");
+            break;
+        }
+    }
+
+    // Submit the score of the last exercise, just like when exiting the prompt.
+    let _ = app.submit_current_score();
+    if let Err(err) = app.save_active_filter() {
+        eprintln!("Failed to save the active filter: {err:#}");
+    }
+    if let Err(err) = app.save_mantra_count() {
+        eprintln!("Failed to save the mantra count: {err:#}");
+    }
+    Ok(exit_code)
+}
 
 /// The entry-point for the command-line interface.
 fn main() -> Result<()> {
+    let args = TraneArgs::parse();
     let mut app = TraneApp::default();
+    if args.read_only {
+        app.set_read_only();
+    }
+
+    // Apply the defaults from the config file. The command-line arguments take precedence.
+    let config = StartupConfig::load();
+    let library_path = args.library_path.clone().or(config.library_path);
+    let history_file = config
+        .history_file
+        .unwrap_or_else(|| HISTORY_FILE.to_string());
+    if config.color == Some(false) {
+        // Colors are disabled before any other thread is started, so changing the environment
+        // is safe.
+        std::env::set_var("NO_COLOR", "1");
+    }
+    if let Some(batch_size) = config.batch_size {
+        app.set_default_batch_size(batch_size);
+    }
+    if let Some(behavior) = config.interrupt {
+        app.set_interrupt_behavior(behavior);
+    }
+    if let Some(auto_answer) = config.auto_answer {
+        app.set_auto_answer(auto_answer);
+    }
+    if let Some(reveal_delay) = config.reveal_delay {
+        app.set_reveal_delay(reveal_delay);
+    }
+    app.set_history_path(Path::new(&history_file));
+    if args.no_pager || args.script.is_some() {
+        pager::disable_paging();
+    }
+
+    // Run the script non-interactively if one was given.
+    if let Some(script_path) = &args.script {
+        let mut exit_code = match &library_path {
+            Some(library_path) => match app.open_library(library_path) {
+                Ok(()) => 0,
+                Err(err) => {
+                    eprintln!("Error: {err:#}");
+                    EXIT_SETUP_FAILED
+                }
+            },
+            None => 0,
+        };
+        if exit_code == 0 {
+            exit_code = run_script(&mut app, script_path, args.continue_on_error, args.strict)
+                .unwrap_or_else(|err| {
+                    eprintln!("Error: {err:#}");
+                    EXIT_SETUP_FAILED
+                });
+        }
 
-    let config = Config::builder()
+        // Drop the app so that the library is closed before exiting.
+        drop(app);
+        if exit_code != 0 {
+            std::process::exit(exit_code);
+        }
+        return Ok(());
+    }
+
+    let color_mode = if config.color == Some(false) {
+        ColorMode::Disabled
+    } else {
+        ColorMode::Enabled
+    };
+    let editor_config = Config::builder()
         .auto_add_history(true)
         .max_history_size(2500)?
-        .color_mode(ColorMode::Enabled)
+        .color_mode(color_mode)
         .history_ignore_space(true)
         .build();
 
-    let mut rl = Editor::<MyHelper, FileHistory>::with_config(config)?;
-    let helper = MyHelper::new();
+    let mut rl = Editor::<MyHelper, FileHistory>::with_config(editor_config)?;
+    let helper = MyHelper::new(app.unit_ids_handle());
     rl.set_helper(Some(helper));
 
-    let history_path = std::path::Path::new(".trane_history");
+    let history_path = Path::new(&history_file);
     if !history_path.exists() {
         match std::fs::File::create(history_path) {
             Ok(_) => {}
@@ -54,64 +307,47 @@ fn main() -> Result<()> {
             }
         }
     }
-    match rl.load_history(history_path) {
-        Ok(()) => (),
-        Err(e) => {
-            eprintln!("Failed to load history file at .trane_history: {e}");
-        }
-    }
+    load_history(rl.history_mut(), history_path);
 
     print!("{}", TraneApp::startup_message());
+    if args.read_only {
+        println!("Read-only mode is on. Scores and other changes will not be saved.");
+        println!();
+    }
+
+    // Open the library given on the command line, if any. The prompt starts even if the library
+    // cannot be opened so that a different one can be opened from there.
+    if let Some(library_path) = &library_path {
+        match app.open_library(library_path) {
+            Ok(()) => println!("Successfully opened course library at {library_path}"),
+            Err(err) => eprintln!("Error: {err:#}"),
+        }
+    }
+    // Whether to submit the score of the current exercise after leaving the prompt.
+    let mut submit_score = false;
     loop {
-        let readline = rl.readline("trane >> ");
+        let readline = rl.readline(&app.prompt());
 
         match readline {
-            Ok(line) => {
-                // Trim any blank space from the line.
-                let line = line.trim();
-
-                // Ignore comments and empty lines.
-                if line.starts_with('#') || line.eq("") {
-                    continue;
-                };
-
-                // Split the line into a vector of arguments. Add an initial argument with value
-                // "trane" if the line doesn't have it, so the parser can recognize the input.
-                let split: Vec<&str> = line.split(' ').collect();
-                let mut args = if !split.is_empty() && split[0] == "trane" {
-                    vec![]
-                } else {
-                    vec!["trane"]
-                };
-                args.extend(split);
-
-                // Parse the arguments.
-                let cli = TraneCli::try_parse_from(args.iter());
-                if cli.is_err() {
-                    println!("{}", cli.unwrap_err());
+            Ok(line) => match execute_line(&mut app, &line) {
+                LineResult::Continue | LineResult::Failed => continue,
+                LineResult::Quit => break,
+            },
+            Err(ReadlineError::Interrupted) => match app.interrupt_behavior() {
+                InterruptBehavior::ClearLine => {
+                    println!("Press CTRL-D or use the quit command to exit");
                     continue;
                 }
-
-                // Execute the subcommand.
-                match cli.unwrap().execute_subcommand(&mut app) {
-                    Ok(continue_execution) => {
-                        if continue_execution {
-                            continue;
-                        }
-                        break;
-                    }
-                    Err(err) => println!("Error: {err:#}"),
+                InterruptBehavior::Quit => {
+                    // Submit the current score before exiting, just like on EOF.
+                    submit_score = true;
+                    println!("Interrupted: Exiting");
+                    break;
                 }
-            }
-            Err(ReadlineError::Interrupted) => {
-                println!("Press CTRL-D or use the quit command to exit");
-                continue;
-            }
+                InterruptBehavior::Ignore => {}
+            },
             Err(ReadlineError::Eof) => {
-                // Submit the current score before exiting. Ignore the error because it's not
-                // guaranteed an instance of Trane is open.
-                let _ = app.submit_current_score();
-
+                submit_score = true;
                 println!("EOF: Exiting");
                 break;
             }
@@ -122,11 +358,88 @@ fn main() -> Result<()> {
         }
     }
 
+    // Save the history and drop the editor before anything else, so that the terminal is given
+    // back in its original state even if the remaining steps take a while or fail.
     match rl.save_history(history_path) {
         Ok(()) => (),
         Err(e) => {
-            eprintln!("Failed to save history to file .trane_history: {e}");
+            eprintln!("Failed to save history to file {history_file}: {e}");
         }
     }
+    drop(rl);
+
+    // Submit the current score before exiting. Ignore the error because it's not guaranteed an
+    // instance of Trane is open.
+    if submit_score {
+        let _ = app.submit_current_score();
+    }
+    if let Err(err) = app.save_active_filter() {
+        eprintln!("Failed to save the active filter: {err:#}");
+    }
+    if let Err(err) = app.save_mantra_count() {
+        eprintln!("Failed to save the mantra count: {err:#}");
+    }
+    app.show_session_stats();
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a path in the temporary directory that is unique to the given test.
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("trane-cli-{}-{name}", std::process::id()))
+    }
+
+    /// Verifies that a history file that cannot be decoded is backed up and replaced.
+    #[test]
+    fn load_malformed_history() -> Result<()> {
+        let history_path = temp_path("history.txt");
+        let contents = b"#V2\nnext\n\xff\xfe\n";
+        std::fs::write(&history_path, contents)?;
+
+        let mut history = FileHistory::new();
+        let backup_path = load_history(&mut history, &history_path);
+        assert_eq!(backup_path, Some(temp_path("history.txt.bak")));
+        assert!(history.is_empty());
+        assert!(std::fs::read(&history_path)?.is_empty());
+        let backup_path = backup_path.unwrap();
+        assert_eq!(std::fs::read(&backup_path)?, contents);
+
+        std::fs::remove_file(&history_path)?;
+        std::fs::remove_file(&backup_path)?;
+        Ok(())
+    }
+
+    /// Verifies that a valid history file is loaded without making a backup.
+    #[test]
+    fn load_valid_history() -> Result<()> {
+        let history_path = temp_path("valid_history");
+        std::fs::write(&history_path, "#V2\nnext\nanswer\n")?;
+
+        let mut history = FileHistory::new();
+        assert_eq!(load_history(&mut history, &history_path), None);
+        assert_eq!(history.len(), 2);
+        assert!(!history_backup_path(&history_path).exists());
+
+        std::fs::remove_file(&history_path)?;
+        Ok(())
+    }
+
+    /// Verifies that errors other than decoding errors leave the history file untouched.
+    #[test]
+    fn load_unreadable_history() -> Result<()> {
+        // Reading a directory fails with an I/O error that does not mean the file is corrupt.
+        let history_path = temp_path("history_dir");
+        std::fs::create_dir_all(&history_path)?;
+
+        let mut history = FileHistory::new();
+        assert_eq!(load_history(&mut history, &history_path), None);
+        assert!(history_path.is_dir());
+        assert!(!history_backup_path(&history_path).exists());
+
+        std::fs::remove_dir(&history_path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+//! Contains the logic to keep a running total of the mantras recited across sessions. Trane only
+//! counts the mantras recited by the open instance, so the count of each session is added to the
+//! total stored in the library when the session ends.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::path::Path;
+
+use crate::json_file::{read_json, write_json};
+
+/// The name of the file inside the `.trane` directory where the lifetime mantra count is stored.
+pub const MANTRA_COUNT_FILE: &str = "mantra_count.json";
+
+/// Reads the number of mantras recited in all the previous sessions from the given config
+/// directory, or zero if the file does not exist yet.
+pub fn load_lifetime_count(config_dir: &Path) -> Result<usize> {
+    let path = config_dir.join(MANTRA_COUNT_FILE);
+    let Some(value) = read_json::<Value>(&path, "mantra count")? else {
+        return Ok(0);
+    };
+    let count = value
+        .get("lifetime_count")
+        .and_then(Value::as_u64)
+        .with_context(|| format!("invalid mantra count file at {}", path.display()))?;
+    Ok(usize::try_from(count)?)
+}
+
+/// Adds the given number of mantras to the lifetime count stored in the given config directory.
+pub fn add_to_lifetime_count(config_dir: &Path, count: usize) -> Result<()> {
+    let total = load_lifetime_count(config_dir)? + count;
+    let path = config_dir.join(MANTRA_COUNT_FILE);
+    write_json(&path, &json!({ "lifetime_count": total }), "mantra count")
+}
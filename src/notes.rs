@@ -0,0 +1,121 @@
+//! Contains the logic to store the notes the user writes about individual exercises.
+
+use anyhow::Result;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use ustr::Ustr;
+
+use crate::json_file::{read_json, write_json};
+
+/// The name of the file inside the `.trane` directory where the notes are stored.
+pub const NOTES_FILE: &str = "exercise_notes.json";
+
+/// Stores the notes written for each exercise in a JSON file inside the library's config
+/// directory. Notes are keyed by the exercise ID.
+pub(crate) struct ExerciseNotes {
+    /// The path to the JSON file containing the notes.
+    path: PathBuf,
+
+    /// The notes for each exercise, in the order in which they were added.
+    notes: BTreeMap<Ustr, Vec<String>>,
+}
+
+impl ExerciseNotes {
+    /// Opens the notes stored in the given config directory, creating an empty store if the file
+    /// does not exist yet.
+    pub fn new_from_disk(config_dir: &Path) -> Result<Self> {
+        let path = config_dir.join(NOTES_FILE);
+        let notes = read_json(&path, "notes")?.unwrap_or_default();
+        Ok(Self { path, notes })
+    }
+
+    /// Writes the notes to disk.
+    fn save(&self) -> Result<()> {
+        write_json(&self.path, &self.notes, "notes")
+    }
+
+    /// Returns the notes for the given exercise.
+    pub fn get_notes(&self, exercise_id: Ustr) -> &[String] {
+        self.notes.get(&exercise_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Adds a new note to the given exercise.
+    pub fn add_note(&mut self, exercise_id: Ustr, note: &str) -> Result<()> {
+        self.notes
+            .entry(exercise_id)
+            .or_default()
+            .push(note.to_string());
+        self.save()
+    }
+
+    /// Removes all the notes for the given exercise.
+    pub fn clear_notes(&mut self, exercise_id: Ustr) -> Result<()> {
+        if self.notes.remove(&exercise_id).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+/// The name of the file inside the `.trane` directory where the notes attached to trials are
+/// stored.
+pub const TRIAL_NOTES_FILE: &str = "trial_notes.json";
+
+/// Stores the notes attached to individual trials in a JSON file inside the library's config
+/// directory. Trane does not store any text with the trials, so the notes are keyed by the exercise
+/// ID and the timestamp of the trial.
+pub(crate) struct TrialNotes {
+    /// The path to the JSON file containing the notes.
+    path: PathBuf,
+
+    /// The note for each trial, keyed by the exercise ID and then by the timestamp of the trial.
+    notes: BTreeMap<Ustr, BTreeMap<i64, String>>,
+}
+
+impl TrialNotes {
+    /// Opens the trial notes stored in the given config directory, creating an empty store if the
+    /// file does not exist yet.
+    pub fn new_from_disk(config_dir: &Path) -> Result<Self> {
+        let path = config_dir.join(TRIAL_NOTES_FILE);
+        let notes = read_json(&path, "trial notes")?.unwrap_or_default();
+        Ok(Self { path, notes })
+    }
+
+    /// Writes the trial notes to disk.
+    fn save(&self) -> Result<()> {
+        write_json(&self.path, &self.notes, "trial notes")
+    }
+
+    /// Returns the note attached to the trial of the given exercise at the given timestamp.
+    pub fn get_note(&self, exercise_id: Ustr, timestamp: i64) -> Option<&str> {
+        self.notes
+            .get(&exercise_id)
+            .and_then(|notes| notes.get(&timestamp))
+            .map(String::as_str)
+    }
+
+    /// Attaches a note to the trial of the given exercise at the given timestamp.
+    pub fn add_note(&mut self, exercise_id: Ustr, timestamp: i64, note: &str) -> Result<()> {
+        self.notes
+            .entry(exercise_id)
+            .or_default()
+            .insert(timestamp, note.to_string());
+        self.save()
+    }
+
+    /// Removes the note attached to the trial of the given exercise at the given timestamp.
+    pub fn remove_note(&mut self, exercise_id: Ustr, timestamp: i64) -> Result<()> {
+        let Some(notes) = self.notes.get_mut(&exercise_id) else {
+            return Ok(());
+        };
+        if notes.remove(&timestamp).is_none() {
+            return Ok(());
+        }
+        if notes.is_empty() {
+            self.notes.remove(&exercise_id);
+        }
+        self.save()
+    }
+}
@@ -0,0 +1,135 @@
+//! Contains a simple pager used to print long lists one screenful at a time, asking the user whether
+//! to continue after each page, and the logic to send long texts to an external pager. Paging is
+//! only done when the output is a terminal.
+
+use anyhow::Result;
+use std::{
+    env,
+    io::{stdin, stdout, IsTerminal, Write},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicBool, Ordering},
+};
+use termimad::crossterm::terminal;
+
+/// The environment variable that disables paging when set to any value.
+pub const NO_PAGER_ENV: &str = "TRANE_NO_PAGER";
+
+/// The pagers to try, in order, when the `PAGER` environment variable is not set.
+const DEFAULT_PAGERS: [&str; 2] = ["less", "more"];
+
+/// The options passed to `less` when the `LESS` environment variable is not set. They make it
+/// display colors, exit right away if the text fits in one screen, and leave the text on the screen
+/// after exiting.
+const DEFAULT_LESS_OPTIONS: &str = "FRX";
+
+/// Whether paging was disabled with the `--no-pager` flag.
+static PAGING_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Disables paging for the rest of the program.
+pub fn disable_paging() {
+    PAGING_DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Returns whether the output should be paged.
+fn paging_enabled() -> bool {
+    !PAGING_DISABLED.load(Ordering::Relaxed)
+        && env::var_os(NO_PAGER_ENV).is_none()
+        && stdout().is_terminal()
+}
+
+/// Prints the given text through the pager in the `PAGER` environment variable, falling back to
+/// `less` or `more`. The text is printed directly if paging is disabled or no pager can be started.
+pub fn page_text(text: &str) -> Result<()> {
+    if paging_enabled() {
+        let pagers = match env::var("PAGER") {
+            Ok(pager) if !pager.trim().is_empty() => vec![pager],
+            _ => DEFAULT_PAGERS.iter().map(ToString::to_string).collect(),
+        };
+        for pager in pagers {
+            let mut parts = pager.split_whitespace();
+            let Some(program) = parts.next() else {
+                continue;
+            };
+            let mut command = Command::new(program);
+            command.args(parts).stdin(Stdio::piped());
+            if env::var_os("LESS").is_none() {
+                command.env("LESS", DEFAULT_LESS_OPTIONS);
+            }
+            let Ok(mut child) = command.spawn() else {
+                continue;
+            };
+            if let Some(mut child_stdin) = child.stdin.take() {
+                // Ignore the error caused by quitting the pager before reading the whole text.
+                let _ = child_stdin.write_all(text.as_bytes());
+            }
+            child.wait()?;
+            return Ok(());
+        }
+    }
+
+    print!("{text}");
+    stdout().flush()?;
+    Ok(())
+}
+
+/// Prints lines to the terminal, pausing after each screenful until the user asks for more.
+pub(crate) struct Pager {
+    /// The number of lines to print before pausing. If `None`, the output is not paginated.
+    page_size: Option<usize>,
+
+    /// The number of lines printed since the last pause.
+    lines_printed: usize,
+
+    /// Whether the user declined to see more lines.
+    stopped: bool,
+}
+
+impl Pager {
+    /// Creates a new pager whose page size is based on the height of the terminal. If stdout is not
+    /// a terminal or paging is disabled, all the lines are printed without pausing.
+    pub fn new() -> Self {
+        let page_size = if paging_enabled() && stdin().is_terminal() {
+            // Leave room for the prompt.
+            terminal::size()
+                .ok()
+                .map(|(_, rows)| usize::from(rows).saturating_sub(1).max(1))
+        } else {
+            None
+        };
+        Self {
+            page_size,
+            lines_printed: 0,
+            stopped: false,
+        }
+    }
+
+    /// Asks the user whether to continue printing. Returns false if the user declined.
+    fn prompt_more() -> Result<bool> {
+        print!("more? [Y/n] ");
+        stdout().flush()?;
+        let mut answer = String::new();
+        stdin().read_line(&mut answer)?;
+        Ok(!matches!(answer.trim(), "n" | "N" | "no" | "q"))
+    }
+
+    /// Prints the given line, pausing first if a full page has already been printed. Returns false
+    /// once the user declines to see more, in which case the line is not printed and the caller
+    /// should stop.
+    pub fn println(&mut self, line: &str) -> Result<bool> {
+        if self.stopped {
+            return Ok(false);
+        }
+        if let Some(page_size) = self.page_size {
+            if self.lines_printed >= page_size {
+                if !Self::prompt_more()? {
+                    self.stopped = true;
+                    return Ok(false);
+                }
+                self.lines_printed = 0;
+            }
+        }
+        println!("{line}");
+        self.lines_printed += 1;
+        Ok(true)
+    }
+}
@@ -0,0 +1,210 @@
+//! Contains the logic to interactively pick a unit ID from a list of candidates, narrowing the list
+//! down as the user types.
+
+use anyhow::{ensure, Result};
+use std::io::{stdin, stdout, IsTerminal, Write};
+use termimad::crossterm::{
+    cursor::{MoveToColumn, MoveUp},
+    event::{read, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Print, PrintStyledContent, Stylize},
+    terminal::{Clear, ClearType},
+};
+use ustr::Ustr;
+
+use crate::{display::terminal_width, keys::RawModeGuard};
+
+/// The maximum number of candidates shown at once.
+const MAX_VISIBLE_CANDIDATES: usize = 10;
+
+/// Returns the rank of the candidate for the given query, or `None` if the candidate does not
+/// match. A candidate matches if all the characters of the query appear in it in order, ignoring
+/// case. Candidates containing the query as is rank first, and shorter candidates rank before
+/// longer ones.
+fn match_rank(candidate: &str, query: &str) -> Option<(bool, usize)> {
+    let candidate_lower = candidate.to_lowercase();
+    let query = query.to_lowercase();
+    if candidate_lower.contains(&query) {
+        return Some((false, candidate.len()));
+    }
+    let mut remaining = candidate_lower.chars();
+    if query.chars().all(|c| remaining.any(|other| other == c)) {
+        Some((true, candidate.len()))
+    } else {
+        None
+    }
+}
+
+/// Returns the candidates matching the given query, from best to worst. All the candidates are
+/// returned in their original order if the query is empty.
+fn matching_candidates(candidates: &[Ustr], query: &str) -> Vec<Ustr> {
+    if query.is_empty() {
+        return candidates.to_vec();
+    }
+    let mut matches: Vec<_> = candidates
+        .iter()
+        .filter_map(|candidate| match_rank(candidate, query).map(|rank| (rank, *candidate)))
+        .collect();
+    matches.sort();
+    matches
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// The state of the picker while the user is selecting a candidate.
+struct Picker<'a> {
+    /// All the candidates that can be picked.
+    candidates: &'a [Ustr],
+
+    /// The text typed so far to narrow down the candidates.
+    query: String,
+
+    /// The candidates matching the query.
+    matches: Vec<Ustr>,
+
+    /// The index of the selected candidate among the matches.
+    selected: usize,
+
+    /// The index of the first match shown on the screen.
+    offset: usize,
+
+    /// The number of lines drawn in the last frame.
+    lines_drawn: u16,
+}
+
+impl<'a> Picker<'a> {
+    /// Creates a new picker with all the candidates matching.
+    fn new(candidates: &'a [Ustr]) -> Self {
+        Self {
+            candidates,
+            query: String::new(),
+            matches: matching_candidates(candidates, ""),
+            selected: 0,
+            offset: 0,
+            lines_drawn: 0,
+        }
+    }
+
+    /// Updates the matches after the query has changed and selects the best one.
+    fn update_matches(&mut self) {
+        self.matches = matching_candidates(self.candidates, &self.query);
+        self.selected = 0;
+        self.offset = 0;
+    }
+
+    /// Moves the cursor to the start of the last frame and clears it.
+    fn clear(&mut self, out: &mut impl Write) -> Result<()> {
+        if self.lines_drawn > 1 {
+            queue!(out, MoveUp(self.lines_drawn - 1))?;
+        }
+        queue!(out, MoveToColumn(0), Clear(ClearType::FromCursorDown))?;
+        self.lines_drawn = 0;
+        Ok(())
+    }
+
+    /// Draws the query and the visible matches, highlighting the selected one. Long IDs are
+    /// truncated to the width of the terminal so that each match takes up a single line.
+    fn draw(&mut self, out: &mut impl Write) -> Result<()> {
+        self.clear(out)?;
+        let width = terminal_width();
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + MAX_VISIBLE_CANDIDATES {
+            self.offset = self.selected + 1 - MAX_VISIBLE_CANDIDATES;
+        }
+
+        let mut lines = vec![format!("> {}", self.query)];
+        let end = self.matches.len().min(self.offset + MAX_VISIBLE_CANDIDATES);
+        for candidate in &self.matches[self.offset..end] {
+            lines.push(
+                candidate
+                    .chars()
+                    .take(width.saturating_sub(3))
+                    .collect::<String>(),
+            );
+        }
+        if self.matches.is_empty() {
+            lines.push("No matching units".to_string());
+        } else if end < self.matches.len() {
+            lines.push(format!("... {} more", self.matches.len() - end));
+        }
+
+        for (index, line) in lines.iter().enumerate() {
+            if index > 0 {
+                queue!(out, Print("\r\n"))?;
+            }
+            let is_selected = index > 0 && self.offset + index - 1 == self.selected;
+            if index == 0 || index > end - self.offset {
+                queue!(out, Print(line))?;
+            } else if is_selected {
+                queue!(out, PrintStyledContent(format!("  {line}").reverse()))?;
+            } else {
+                queue!(out, Print(format!("  {line}")))?;
+            }
+        }
+        out.flush()?;
+        self.lines_drawn = lines.len() as u16;
+        Ok(())
+    }
+
+    /// Handles key presses until a candidate is selected or the picker is cancelled.
+    fn run(&mut self, out: &mut impl Write) -> Result<Option<Ustr>> {
+        loop {
+            self.draw(out)?;
+            let Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }) = read()?
+            else {
+                continue;
+            };
+
+            match code {
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Enter if !self.matches.is_empty() => {
+                    return Ok(Some(self.matches[self.selected]))
+                }
+                KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+                KeyCode::Down if self.selected + 1 < self.matches.len() => self.selected += 1,
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.update_matches();
+                }
+                KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.query.push(c);
+                    self.update_matches();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Lets the user pick one of the given unit IDs from a list that is narrowed down as they type.
+/// Returns `None` if the user cancels the selection.
+pub(crate) fn pick_unit(description: &str, candidates: &[Ustr]) -> Result<Option<Ustr>> {
+    ensure!(
+        stdin().is_terminal() && stdout().is_terminal(),
+        "picking a unit requires an interactive terminal"
+    );
+    ensure!(!candidates.is_empty(), "there are no units to pick from");
+
+    println!(
+        "Select {description}. Type to filter the list, use the arrow keys to move, and press \
+        Enter to select or Esc to cancel."
+    );
+    let mut picker = Picker::new(candidates);
+    let mut out = stdout();
+    let selection = {
+        let _guard = RawModeGuard::new()?;
+        let selection = picker.run(&mut out);
+        picker.clear(&mut out)?;
+        out.flush()?;
+        selection
+    }?;
+    Ok(selection)
+}
@@ -0,0 +1,63 @@
+//! Contains the logic to remember the exercises shown most recently, so that the scheduler's
+//! occasional immediate repeats can be skipped in short sessions.
+
+use std::collections::VecDeque;
+use ustr::Ustr;
+
+/// The number of recent exercises remembered by default.
+pub const DEFAULT_RECENT_EXERCISES: usize = 3;
+
+/// A ring buffer with the IDs of the last exercises shown to the user.
+pub(crate) struct RecentExercises {
+    /// The maximum number of exercises to remember. A capacity of zero disables the buffer.
+    capacity: usize,
+
+    /// The IDs of the recent exercises, from oldest to newest.
+    exercise_ids: VecDeque<Ustr>,
+}
+
+impl Default for RecentExercises {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_RECENT_EXERCISES,
+            exercise_ids: VecDeque::with_capacity(DEFAULT_RECENT_EXERCISES),
+        }
+    }
+}
+
+impl RecentExercises {
+    /// Returns the maximum number of exercises to remember.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the maximum number of exercises to remember, dropping the oldest ones if needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.exercise_ids.len() > capacity {
+            self.exercise_ids.pop_front();
+        }
+    }
+
+    /// Returns whether the given exercise was shown recently.
+    pub fn contains(&self, exercise_id: Ustr) -> bool {
+        self.exercise_ids.contains(&exercise_id)
+    }
+
+    /// Records that the given exercise was shown, forgetting the oldest exercise if the buffer is
+    /// full.
+    pub fn push(&mut self, exercise_id: Ustr) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.exercise_ids.len() == self.capacity {
+            self.exercise_ids.pop_front();
+        }
+        self.exercise_ids.push_back(exercise_id);
+    }
+
+    /// Forgets all the recent exercises.
+    pub fn clear(&mut self) {
+        self.exercise_ids.clear();
+    }
+}
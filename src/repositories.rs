@@ -0,0 +1,225 @@
+//! Contains the logic to inspect the git repositories managed by Trane and to remember when each
+//! of them was last updated and the ref to which it is pinned, since Trane itself only stores their
+//! IDs and URLs.
+
+use anyhow::{Context, Result};
+use git2::{
+    build::CheckoutBuilder, Branch, BranchType, ErrorClass, Object, Reference, Repository,
+    StatusOptions,
+};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::json_file::{read_json, write_json};
+
+/// The name of the file inside the `.trane` directory where the update times are stored.
+pub const REPOSITORY_UPDATES_FILE: &str = "repository_updates.json";
+
+/// The name of the directory inside the library where Trane clones the managed repositories.
+pub const MANAGED_COURSES_DIR: &str = "managed_courses";
+
+/// The name of the file inside the `.trane` directory where the pinned refs are stored.
+pub const REPOSITORY_REFS_FILE: &str = "repository_refs.json";
+
+/// Stores the last time each managed repository was successfully updated in a JSON file inside
+/// the library's config directory.
+pub(crate) struct RepositoryUpdates {
+    /// The path to the JSON file containing the update times.
+    path: PathBuf,
+
+    /// A map of repository IDs to the timestamp of their last successful update.
+    timestamps: BTreeMap<String, i64>,
+}
+
+impl RepositoryUpdates {
+    /// Opens the update times stored in the given config directory, starting with no entries if
+    /// the file does not exist yet.
+    pub fn new_from_disk(config_dir: &Path) -> Result<Self> {
+        let path = config_dir.join(REPOSITORY_UPDATES_FILE);
+        let timestamps = read_json(&path, "repository updates")?.unwrap_or_default();
+        Ok(Self { path, timestamps })
+    }
+
+    /// Writes the update times to disk.
+    fn save(&self) -> Result<()> {
+        write_json(&self.path, &self.timestamps, "repository updates")
+    }
+
+    /// Returns the timestamp of the last successful update of the given repository, if any.
+    pub fn last_updated(&self, repo_id: &str) -> Option<i64> {
+        self.timestamps.get(repo_id).copied()
+    }
+
+    /// Records that the given repository was updated at the given time.
+    pub fn record_update(&mut self, repo_id: &str, timestamp: i64) -> Result<()> {
+        self.timestamps.insert(repo_id.to_string(), timestamp);
+        self.save()
+    }
+
+    /// Forgets the update time of the given repository.
+    pub fn remove(&mut self, repo_id: &str) -> Result<()> {
+        if self.timestamps.remove(repo_id).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+/// Stores the branch, tag, or commit to which each managed repository is pinned in a JSON file
+/// inside the library's config directory. Trane always clones the default branch, so the pinned
+/// ref is checked out again after every update.
+pub(crate) struct RepositoryRefs {
+    /// The path to the JSON file containing the pinned refs.
+    path: PathBuf,
+
+    /// A map of repository IDs to the ref to which they are pinned.
+    refs: BTreeMap<String, String>,
+}
+
+impl RepositoryRefs {
+    /// Opens the pinned refs stored in the given config directory, starting with no entries if the
+    /// file does not exist yet.
+    pub fn new_from_disk(config_dir: &Path) -> Result<Self> {
+        let path = config_dir.join(REPOSITORY_REFS_FILE);
+        let refs = read_json(&path, "repository refs")?.unwrap_or_default();
+        Ok(Self { path, refs })
+    }
+
+    /// Writes the pinned refs to disk.
+    fn save(&self) -> Result<()> {
+        write_json(&self.path, &self.refs, "repository refs")
+    }
+
+    /// Returns the ref to which the given repository is pinned, if any.
+    pub fn get(&self, repo_id: &str) -> Option<&str> {
+        self.refs.get(repo_id).map(String::as_str)
+    }
+
+    /// Pins the given repository to the given ref.
+    pub fn pin(&mut self, repo_id: &str, git_ref: &str) -> Result<()> {
+        self.refs.insert(repo_id.to_string(), git_ref.to_string());
+        self.save()
+    }
+
+    /// Forgets the pinned ref of the given repository.
+    pub fn remove(&mut self, repo_id: &str) -> Result<()> {
+        if self.refs.remove(repo_id).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns whether the given error was caused by a network failure, such as a host that could not
+/// be reached or a connection that was dropped, as opposed to a problem with the repository itself.
+/// Trane clones the repositories with libgit2, so the errors it reports are inspected.
+pub fn is_network_error(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<git2::Error>())
+        .any(|git_err| {
+            matches!(
+                git_err.class(),
+                ErrorClass::Net | ErrorClass::Http | ErrorClass::Ssl | ErrorClass::Os
+            )
+        })
+}
+
+/// The name of the remote with which Trane clones the managed repositories.
+const REMOTE_NAME: &str = "origin";
+
+/// Opens the git repository in the given directory.
+fn open_repo(repo_dir: &Path) -> Result<Repository> {
+    Repository::open(repo_dir)
+        .with_context(|| format!("failed to open git repository at {}", repo_dir.display()))
+}
+
+/// Returns the commit to which the given branch, tag, or commit points in the given repository.
+/// Like `git checkout`, a name that only exists as a branch of the remote creates a local branch
+/// that tracks it.
+fn resolve_ref<'a>(
+    repo: &'a Repository,
+    git_ref: &str,
+) -> Result<(Object<'a>, Option<Reference<'a>>)> {
+    if let Ok(resolved) = repo.revparse_ext(git_ref) {
+        return Ok(resolved);
+    }
+
+    let remote_name = format!("{REMOTE_NAME}/{git_ref}");
+    let remote_branch = repo
+        .find_branch(&remote_name, BranchType::Remote)
+        .with_context(|| format!("no branch, tag, or commit named {git_ref}"))?;
+    let commit = remote_branch.get().peel_to_commit()?;
+    let mut branch = repo.branch(git_ref, &commit, false)?;
+    branch.set_upstream(Some(&remote_name))?;
+    Ok((commit.into_object(), Some(branch.into_reference())))
+}
+
+/// Checks out the given branch, tag, or commit in the given repository. Branches are checked out
+/// as the current branch, while tags and commits leave the repository in a detached state.
+pub fn checkout_ref(repo_dir: &Path, git_ref: &str) -> Result<()> {
+    let repo = open_repo(repo_dir)?;
+    let (object, reference) = resolve_ref(&repo, git_ref)?;
+    let commit = object.peel_to_commit()?;
+    repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))
+        .with_context(|| format!("failed to check out {git_ref} in {}", repo_dir.display()))?;
+    match reference
+        .filter(Reference::is_branch)
+        .and_then(|r| r.name().map(str::to_string))
+    {
+        Some(branch_name) => repo.set_head(&branch_name)?,
+        None => repo.set_head_detached(commit.id())?,
+    }
+    Ok(())
+}
+
+/// Fetches the latest state of the remote of the given repository without changing the local
+/// checkout.
+pub fn fetch(repo_dir: &Path) -> Result<()> {
+    let repo = open_repo(repo_dir)?;
+    let mut remote = repo.find_remote(REMOTE_NAME)?;
+    remote
+        .fetch(&[] as &[&str], None, None)
+        .with_context(|| format!("failed to fetch {}", repo_dir.display()))
+}
+
+/// Returns the number of commits the local checkout of a repository is behind its remote branch,
+/// or `None` if it cannot be determined, for example because the checkout is pinned to a tag or a
+/// commit.
+pub fn commits_behind(repo_dir: &Path) -> Option<usize> {
+    let repo = Repository::open(repo_dir).ok()?;
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+    let local = head.target()?;
+    let upstream = Branch::wrap(head).upstream().ok()?.get().target()?;
+    let (_, behind) = repo.graph_ahead_behind(local, upstream).ok()?;
+    Some(behind)
+}
+
+/// Returns a short description of the state of the local checkout of a repository compared to
+/// its remote. The remote is not fetched, so the comparison uses the last known state of the
+/// remote branch.
+pub fn sync_status(repo_dir: &Path) -> String {
+    if !repo_dir.exists() {
+        return "missing".to_string();
+    }
+    let Ok(repo) = Repository::open(repo_dir) else {
+        return "unknown".to_string();
+    };
+    let mut options = StatusOptions::new();
+    options.include_untracked(true);
+    let Ok(statuses) = repo.statuses(Some(&mut options)) else {
+        return "unknown".to_string();
+    };
+    if !statuses.is_empty() {
+        return "dirty".to_string();
+    }
+    match commits_behind(repo_dir) {
+        Some(0) => "up to date".to_string(),
+        Some(count) => format!("behind by {count}"),
+        None => "unknown".to_string(),
+    }
+}
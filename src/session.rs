@@ -0,0 +1,78 @@
+//! Contains the logic to keep track of the exercises scored during the current session of the
+//! command-line interface.
+
+use std::time::{Duration, Instant};
+use trane::data::MasteryScore;
+
+/// The number of exercises scored with each mastery score since the CLI was started.
+pub(crate) struct SessionStats {
+    /// The time at which the session started.
+    start: Instant,
+
+    /// The number of exercises scored with each score, from one to five.
+    score_counts: [usize; 5],
+}
+
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            score_counts: [0; 5],
+        }
+    }
+}
+
+impl SessionStats {
+    /// Returns the index in the score counts for the given score.
+    fn score_index(score: &MasteryScore) -> usize {
+        match score {
+            MasteryScore::One => 0,
+            MasteryScore::Two => 1,
+            MasteryScore::Three => 2,
+            MasteryScore::Four => 3,
+            MasteryScore::Five => 4,
+        }
+    }
+
+    /// Formats the duration as hours, minutes, and seconds.
+    fn format_duration(duration: Duration) -> String {
+        let seconds = duration.as_secs();
+        format!(
+            "{}h {:02}m {:02}s",
+            seconds / 3600,
+            (seconds % 3600) / 60,
+            seconds % 60
+        )
+    }
+
+    /// Records that an exercise was scored with the given score.
+    pub fn record(&mut self, score: &MasteryScore) {
+        self.score_counts[Self::score_index(score)] += 1;
+    }
+
+    /// Removes a score previously recorded with `record`.
+    pub fn remove(&mut self, score: &MasteryScore) {
+        let count = &mut self.score_counts[Self::score_index(score)];
+        *count = count.saturating_sub(1);
+    }
+
+    /// Prints the number of exercises scored during the session, the distribution of their scores,
+    /// and the time elapsed since the session started.
+    pub fn print(&self) {
+        let num_scored: usize = self.score_counts.iter().sum();
+        if num_scored == 0 {
+            println!("No exercises practiced this session");
+            return;
+        }
+
+        println!("Session summary:");
+        println!("Exercises scored: {num_scored}");
+        for (score, count) in (1..).zip(self.score_counts) {
+            println!("  Score {score}: {count}");
+        }
+        println!(
+            "Elapsed time: {}",
+            Self::format_duration(self.start.elapsed())
+        );
+    }
+}
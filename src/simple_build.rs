@@ -1,10 +1,21 @@
 //! Command line tool that takes a simple knowledge base course configuration file and builds the
-//! course in the current directory.
+//! course in the current directory. The lessons can also be generated from a flat list of
+//! flashcards, stored either in a CSV file or in a directory of markdown files.
 
-use anyhow::Result;
+use anyhow::{bail, ensure, Context, Result};
 use clap::Parser;
-use std::{env::current_dir, fs};
-use trane::course_builder::knowledge_base_builder::SimpleKnowledgeBaseCourse;
+use std::{
+    env::current_dir,
+    fs,
+    path::{Path, PathBuf},
+};
+use trane::{
+    course_builder::knowledge_base_builder::{
+        SimpleKnowledgeBaseCourse, SimpleKnowledgeBaseExercise, SimpleKnowledgeBaseLesson,
+    },
+    data::course_generator::knowledge_base::{EXERCISE_BACK_SUFFIX, EXERCISE_FRONT_SUFFIX},
+};
+use ustr::Ustr;
 
 #[derive(Debug, Parser)]
 #[clap(name = "trane")]
@@ -15,6 +26,156 @@ pub(crate) struct SimpleBuild {
 
     #[clap(help = "The directory to which to build the course")]
     directory: String,
+
+    #[clap(help = "The path to a CSV file with one front,back pair per line. \
+        The cards are added to the course as lessons with sequential dependencies")]
+    #[clap(long, conflicts_with = "markdown_dir")]
+    csv: Option<String>,
+
+    #[clap(
+        help = "The path to a directory with <name>.front.md and optional <name>.back.md \
+        files. The cards are added to the course as lessons with sequential dependencies"
+    )]
+    #[clap(long)]
+    markdown_dir: Option<String>,
+
+    #[clap(help = "The number of cards in each lesson generated from a CSV file or a directory")]
+    #[clap(long, default_value = "10")]
+    lesson_size: usize,
+}
+
+/// A flashcard read from a CSV file or a directory of markdown files.
+struct Card {
+    /// The short ID of the exercise generated from this card.
+    short_id: String,
+
+    /// The contents of the front of the card.
+    front: String,
+
+    /// The contents of the back of the card. Empty if the card has no back.
+    back: String,
+}
+
+/// Splits a line of a CSV file into its fields. Fields can be quoted to include commas, and quotes
+/// inside quoted fields are escaped by doubling them.
+fn parse_csv_line(line: &str) -> Result<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    ensure!(!in_quotes, "unterminated quoted field in line: {line}");
+    fields.push(field);
+    Ok(fields)
+}
+
+/// Reads the cards from a CSV file with a front and an optional back field per line. Empty lines
+/// are ignored.
+fn read_csv_cards(path: &Path) -> Result<Vec<Card>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read CSV file at {}", path.display()))?;
+    let mut cards = Vec::new();
+    for (line_number, line) in (1..).zip(contents.lines()) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut fields = parse_csv_line(line)
+            .with_context(|| format!("failed to parse line {line_number} of the CSV file"))?;
+        if fields.len() > 2 {
+            bail!("line {line_number} of the CSV file has more than two fields");
+        }
+        let back = if fields.len() == 2 {
+            fields.pop().unwrap_or_default()
+        } else {
+            String::new()
+        };
+        let front = fields.pop().unwrap_or_default();
+        cards.push(Card {
+            short_id: format!("card_{line_number}"),
+            front,
+            back,
+        });
+    }
+    Ok(cards)
+}
+
+/// Reads the cards from a directory containing a `<name>.front.md` file and an optional
+/// `<name>.back.md` file for each card. The cards are sorted by name.
+fn read_markdown_cards(directory: &Path) -> Result<Vec<Card>> {
+    let mut front_paths: Vec<PathBuf> = fs::read_dir(directory)
+        .with_context(|| format!("failed to read directory {}", directory.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(EXERCISE_FRONT_SUFFIX))
+        })
+        .collect();
+    front_paths.sort();
+
+    let mut cards = Vec::new();
+    for front_path in front_paths {
+        let file_name = front_path.file_name().unwrap().to_string_lossy();
+        let short_id = file_name
+            .trim_end_matches(EXERCISE_FRONT_SUFFIX)
+            .to_string();
+        let back_path = directory.join(format!("{short_id}{EXERCISE_BACK_SUFFIX}"));
+        let back = if back_path.exists() {
+            fs::read_to_string(&back_path)?
+        } else {
+            String::new()
+        };
+        cards.push(Card {
+            short_id,
+            front: fs::read_to_string(&front_path)?,
+            back,
+        });
+    }
+    Ok(cards)
+}
+
+/// Groups the cards into lessons of the given size. Each lesson depends on the previous one so
+/// that the cards are introduced in order.
+fn generate_lessons(
+    cards: Vec<Card>,
+    lesson_size: usize,
+) -> Result<Vec<SimpleKnowledgeBaseLesson>> {
+    ensure!(lesson_size > 0, "the lesson size must be greater than zero");
+
+    let mut lessons: Vec<SimpleKnowledgeBaseLesson> = Vec::new();
+    for (index, chunk) in (1..).zip(cards.chunks(lesson_size)) {
+        let exercises = chunk
+            .iter()
+            .map(|card| SimpleKnowledgeBaseExercise {
+                short_id: card.short_id.clone(),
+                front: card.front.lines().map(ToString::to_string).collect(),
+                back: card.back.lines().map(ToString::to_string).collect(),
+            })
+            .collect();
+        lessons.push(SimpleKnowledgeBaseLesson {
+            short_id: Ustr::from(&format!("lesson_{index}")),
+            dependencies: lessons
+                .last()
+                .map(|lesson| lesson.short_id)
+                .into_iter()
+                .collect(),
+            superseded: vec![],
+            exercises,
+            metadata: None,
+            additional_files: vec![],
+        });
+    }
+    Ok(lessons)
 }
 
 fn main() -> Result<()> {
@@ -23,9 +184,21 @@ fn main() -> Result<()> {
 
     // Parse the input file and build the course.
     let config_path = &current_dir()?.join(args.config_file);
-    let simple_course =
+    let mut simple_course =
         serde_json::from_str::<SimpleKnowledgeBaseCourse>(&fs::read_to_string(config_path)?)?;
 
+    // Generate lessons from the list of cards, if one was given.
+    let cards = match (&args.csv, &args.markdown_dir) {
+        (Some(csv), _) => Some(read_csv_cards(&current_dir()?.join(csv))?),
+        (_, Some(markdown_dir)) => Some(read_markdown_cards(&current_dir()?.join(markdown_dir))?),
+        (None, None) => None,
+    };
+    if let Some(cards) = cards {
+        simple_course
+            .lessons
+            .extend(generate_lessons(cards, args.lesson_size)?);
+    }
+
     // Build the course.
     let directory = &current_dir()?.join(&args.directory);
     simple_course.build(directory)
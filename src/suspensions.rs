@@ -0,0 +1,80 @@
+//! Contains the logic to temporarily suspend units so that their exercises are not shown until the
+//! suspension expires. Trane has no concept of suspensions, so the exercises of suspended units are
+//! removed from the batches after they are selected by the scheduler.
+
+use anyhow::{bail, Result};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use trane::data::ExerciseManifest;
+use ustr::Ustr;
+
+use crate::json_file::{read_json, write_json};
+
+/// The name of the file inside the `.trane` directory where the suspensions are stored.
+pub const SUSPENSIONS_FILE: &str = "suspensions.json";
+
+/// Stores the time at which the suspension of each unit expires in a JSON file inside the
+/// library's config directory.
+pub(crate) struct Suspensions {
+    /// The path to the JSON file containing the suspensions.
+    path: PathBuf,
+
+    /// A map of unit IDs to the timestamp at which their suspension expires.
+    expirations: BTreeMap<Ustr, i64>,
+}
+
+impl Suspensions {
+    /// Opens the suspensions stored in the given config directory, starting with no entries if the
+    /// file does not exist yet. Suspensions that expired before the given time are dropped.
+    pub fn new_from_disk(config_dir: &Path, now: i64) -> Result<Self> {
+        let path = config_dir.join(SUSPENSIONS_FILE);
+        let mut expirations: BTreeMap<Ustr, i64> =
+            read_json(&path, "suspensions")?.unwrap_or_default();
+        expirations.retain(|_, expiration| *expiration > now);
+        Ok(Self { path, expirations })
+    }
+
+    /// Writes the suspensions to disk.
+    fn save(&self) -> Result<()> {
+        write_json(&self.path, &self.expirations, "suspensions")
+    }
+
+    /// Returns the IDs of the suspended units and the time at which their suspensions expire,
+    /// ignoring the suspensions that expired before the given time.
+    pub fn get_suspensions(&self, now: i64) -> Vec<(Ustr, i64)> {
+        self.expirations
+            .iter()
+            .filter(|(_, expiration)| **expiration > now)
+            .map(|(unit_id, expiration)| (*unit_id, *expiration))
+            .collect()
+    }
+
+    /// Returns whether the given exercise, its lesson, or its course is suspended at the given
+    /// time.
+    pub fn is_suspended(&self, manifest: &ExerciseManifest, now: i64) -> bool {
+        [manifest.id, manifest.lesson_id, manifest.course_id]
+            .iter()
+            .any(|unit_id| {
+                self.expirations
+                    .get(unit_id)
+                    .is_some_and(|expiration| *expiration > now)
+            })
+    }
+
+    /// Suspends the given unit until the given time. Suspending a unit that is already suspended
+    /// replaces the previous expiration.
+    pub fn suspend(&mut self, unit_id: Ustr, expiration: i64) -> Result<()> {
+        self.expirations.insert(unit_id, expiration);
+        self.save()
+    }
+
+    /// Lifts the suspension of the given unit.
+    pub fn resume(&mut self, unit_id: Ustr) -> Result<()> {
+        if self.expirations.remove(&unit_id).is_none() {
+            bail!("unit {unit_id} is not suspended");
+        }
+        self.save()
+    }
+}